@@ -33,7 +33,7 @@ use sp_runtime::{
 };
 
 use astar_primitives::{
-    dapp_staking::{CycleConfiguration, SmartContract, StakingRewardHandler},
+    dapp_staking::{CycleConfiguration, RewardPayoutError, SmartContract, StakingRewardHandler},
     oracle::PriceProvider,
     Balance, BlockNumber,
 };
@@ -125,7 +125,7 @@ impl StakingRewardHandler<AccountId> for DummyStakingRewardHandler {
         Balance::from(3_000_000_u128)
     }
 
-    fn payout_reward(beneficiary: &AccountId, reward: Balance) -> Result<(), ()> {
+    fn payout_reward(beneficiary: &AccountId, reward: Balance) -> Result<(), RewardPayoutError> {
         let _ = Balances::mint_into(beneficiary, reward);
         Ok(())
     }
@@ -53,7 +53,7 @@ pub use sp_std::vec::Vec;
 use astar_primitives::{
     dapp_staking::{
         AccountCheck, CycleConfiguration, DAppId, EraNumber, Observer as DAppStakingObserver,
-        PeriodNumber, SmartContractHandle, StakingRewardHandler, TierId,
+        PeriodNumber, RewardPools, SmartContractHandle, StakingRewardHandler, TierId,
     },
     oracle::PriceProvider,
     Balance, BlockNumber,
@@ -1382,7 +1382,7 @@ pub mod pallet {
 
             // Get reward destination, and deposit the reward.
             let beneficiary = dapp_info.reward_beneficiary();
-            T::StakingRewardHandler::payout_reward(&beneficiary, amount)
+            T::StakingRewardHandler::payout_dapp_reward(&beneficiary, tier_id, amount)
                 .map_err(|_| Error::<T>::RewardPayoutFailed)?;
 
             // Write back updated struct to prevent double reward claims
@@ -1804,10 +1804,11 @@ pub mod pallet {
 
             // At this point it's clear that an era change will happen
             let mut era_info = CurrentEraInfo::<T>::get();
+            let old_total_staked = era_info.total_staked_amount();
 
             let current_era = protocol_state.era;
             let next_era = current_era.saturating_add(1);
-            let (maybe_period_event, era_reward) = match protocol_state.subperiod() {
+            let (maybe_period_event, era_reward, bonus_pool) = match protocol_state.subperiod() {
                 // Voting subperiod only lasts for one 'prolonged' era
                 Subperiod::Voting => {
                     // For the sake of consistency, we put zero reward into storage. There are no rewards for the voting subperiod.
@@ -1837,6 +1838,7 @@ pub mod pallet {
                             number: protocol_state.period_number(),
                         }),
                         era_reward,
+                        Balance::zero(),
                     )
                 }
                 Subperiod::BuildAndEarn => {
@@ -1862,6 +1864,12 @@ pub mod pallet {
                         #[cfg(feature = "runtime-benchmarks")]
                         TierAssignment::Dummy => (DAppTierRewardsFor::<T>::default(), 0),
                     };
+
+                    // Inform observers about the new tier assignment.
+                    consumed_weight.saturating_accrue(T::Observers::on_tiers_assigned(
+                        current_era,
+                        &dapp_tier_rewards.dapps,
+                    ));
                     DAppTiers::<T>::insert(&current_era, dapp_tier_rewards);
 
                     consumed_weight
@@ -1914,6 +1922,7 @@ pub mod pallet {
                                 number: protocol_state.period_number(),
                             }),
                             era_reward,
+                            bonus_reward_pool,
                         )
                     } else {
                         let next_era_start_block =
@@ -1926,11 +1935,30 @@ pub mod pallet {
                             T::WeightInfo::on_initialize_build_and_earn_to_build_and_earn(),
                         );
 
-                        (None, era_reward)
+                        (None, era_reward, Balance::zero())
                     }
                 }
             };
 
+            // Inform observers about the final reward pools of the ending era.
+            consumed_weight.saturating_accrue(T::Observers::on_era_reward_finalized(
+                current_era,
+                &RewardPools {
+                    staker: era_reward.staker_reward_pool,
+                    dapp: era_reward.dapp_reward_pool,
+                    bonus: bonus_pool,
+                },
+            ));
+
+            // Inform observers in case total staked amount has changed with the new era.
+            let new_total_staked = era_info.total_staked_amount();
+            if old_total_staked != new_total_staked {
+                consumed_weight.saturating_accrue(T::Observers::on_total_stake_changed(
+                    old_total_staked,
+                    new_total_staked,
+                ));
+            }
+
             // Update storage items
             protocol_state.era = next_era;
             ActiveProtocolState::<T>::put(protocol_state);
@@ -37,7 +37,10 @@ use sp_runtime::{
 use sp_std::cell::RefCell;
 
 use astar_primitives::{
-    dapp_staking::{Observer as DappStakingObserver, SmartContract},
+    dapp_staking::{
+        DAppTierAssignment, Observer as DappStakingObserver, RewardPayoutError, RewardPools,
+        SmartContract,
+    },
     Balance, BlockNumber,
 };
 
@@ -114,6 +117,9 @@ impl PriceProvider for DummyPriceProvider {
 thread_local! {
     pub(crate) static DOES_PAYOUT_SUCCEED: RefCell<bool> = RefCell::new(false);
     pub(crate) static BLOCK_BEFORE_NEW_ERA: RefCell<EraNumber> = RefCell::new(0);
+    pub(crate) static ERA_REWARD_FINALIZED: RefCell<Option<(EraNumber, RewardPools)>> = RefCell::new(None);
+    pub(crate) static TIERS_ASSIGNED: RefCell<Option<(EraNumber, DAppTierAssignment)>> = RefCell::new(None);
+    pub(crate) static TOTAL_STAKE_CHANGED: RefCell<Option<(Balance, Balance)>> = RefCell::new(None);
 }
 
 pub struct DummyStakingRewardHandler;
@@ -129,12 +135,12 @@ impl StakingRewardHandler<AccountId> for DummyStakingRewardHandler {
         Balance::from(3_000_000_u128)
     }
 
-    fn payout_reward(beneficiary: &AccountId, reward: Balance) -> Result<(), ()> {
+    fn payout_reward(beneficiary: &AccountId, reward: Balance) -> Result<(), RewardPayoutError> {
         if DOES_PAYOUT_SUCCEED.with(|v| v.borrow().clone()) {
             let _ = Balances::mint_into(beneficiary, reward);
             Ok(())
         } else {
-            Err(())
+            Err(RewardPayoutError::Failed)
         }
     }
 }
@@ -183,6 +189,21 @@ impl DappStakingObserver for DummyDappStakingObserver {
         BLOCK_BEFORE_NEW_ERA.with(|v| *v.borrow_mut() = next_era);
         Weight::from_parts(1, 2)
     }
+
+    fn on_era_reward_finalized(era: EraNumber, pools: &RewardPools) -> Weight {
+        ERA_REWARD_FINALIZED.with(|v| *v.borrow_mut() = Some((era, *pools)));
+        Weight::zero()
+    }
+
+    fn on_tiers_assigned(era: EraNumber, assignment: &DAppTierAssignment) -> Weight {
+        TIERS_ASSIGNED.with(|v| *v.borrow_mut() = Some((era, assignment.clone())));
+        Weight::zero()
+    }
+
+    fn on_total_stake_changed(old: Balance, new: Balance) -> Weight {
+        TOTAL_STAKE_CHANGED.with(|v| *v.borrow_mut() = Some((old, new)));
+        Weight::zero()
+    }
 }
 
 pub(crate) const BLACKLISTED_ACCOUNT: AccountId = 789456123;
@@ -18,8 +18,8 @@
 
 use crate::test::{mock::*, testing_utils::*};
 use crate::{
-    pallet::Config, ActiveProtocolState, DAppId, EraRewards, Error, Event, ForcingType,
-    IntegratedDApps, Ledger, NextDAppId, PeriodNumber, Safeguard, StakerInfo, Subperiod,
+    pallet::Config, ActiveProtocolState, DAppId, DAppTiers, EraRewards, Error, Event, ForcingType,
+    IntegratedDApps, Ledger, NextDAppId, PeriodEnd, PeriodNumber, Safeguard, StakerInfo, Subperiod,
     TierConfig,
 };
 
@@ -34,7 +34,9 @@ use frame_support::{
 use sp_runtime::traits::Zero;
 
 use astar_primitives::{
-    dapp_staking::{CycleConfiguration, EraNumber, SmartContractHandle},
+    dapp_staking::{
+        CycleConfiguration, DAppTierAssignment, EraNumber, RewardPools, SmartContractHandle,
+    },
     Balance, BlockNumber,
 };
 
@@ -2735,6 +2737,127 @@ fn observer_pre_new_era_block_works() {
     })
 }
 
+#[test]
+fn observer_era_reward_finalized_works() {
+    ExtBuilder::build().execute_with(|| {
+        fn observed_value() -> Option<(EraNumber, RewardPools)> {
+            ERA_REWARD_FINALIZED.with(|v| v.borrow().clone())
+        }
+
+        // 1. Sanity check
+        assert!(observed_value().is_none());
+
+        // 2. Voting subperiod era has no rewards.
+        advance_to_next_era();
+        assert_eq!(observed_value(), Some((1, RewardPools::default())));
+
+        // 3. Build&earn era pools must match the stored era reward.
+        advance_to_next_era();
+        let era_reward = EraRewards::<Test>::get(DappStaking::era_reward_span_index(2))
+            .and_then(|span| span.get(2).cloned())
+            .expect("Entry must exist.");
+        assert_eq!(
+            observed_value(),
+            Some((
+                2,
+                RewardPools {
+                    staker: era_reward.staker_reward_pool,
+                    dapp: era_reward.dapp_reward_pool,
+                    bonus: Zero::zero(),
+                }
+            ))
+        );
+
+        // 4. Final era of the period also includes the bonus reward pool.
+        advance_to_next_period();
+        let period_end_info = PeriodEnd::<Test>::get(1).expect("Entry must exist.");
+        let (era, pools) = observed_value().expect("Must be set.");
+        assert_eq!(era, period_end_info.final_era);
+        assert_eq!(pools.bonus, period_end_info.bonus_reward_pool);
+    })
+}
+
+#[test]
+fn observer_tiers_assigned_works() {
+    ExtBuilder::build().execute_with(|| {
+        fn observed_value() -> Option<(EraNumber, DAppTierAssignment)> {
+            TIERS_ASSIGNED.with(|v| v.borrow().clone())
+        }
+
+        // Register smart contract, lock&stake some amount
+        let smart_contract = MockSmartContract::wasm(1 as AccountId);
+        assert_register(1, &smart_contract);
+
+        let account = 2;
+        let amount = 300;
+        assert_lock(account, amount);
+        assert_stake(account, &smart_contract, amount);
+
+        // 1. No tiers are assigned at the end of the voting subperiod.
+        advance_to_next_era();
+        assert!(observed_value().is_none());
+
+        // 2. Build&earn era tier assignment must match the stored one.
+        advance_to_next_era();
+        let dapp_id = IntegratedDApps::<Test>::get(&smart_contract)
+            .expect("Entry must exist.")
+            .id;
+        let (era, assignment) = observed_value().expect("Must be set.");
+        assert_eq!(era, 2);
+        assert!(assignment.contains_key(&dapp_id));
+        assert_eq!(
+            assignment,
+            DAppTiers::<Test>::get(era)
+                .expect("Entry must exist.")
+                .dapps
+                .into_inner()
+        );
+    })
+}
+
+#[test]
+fn observer_total_stake_changed_works() {
+    ExtBuilder::build().execute_with(|| {
+        fn observed_delta() -> Option<i128> {
+            TOTAL_STAKE_CHANGED.with(|v| v.borrow().map(|(old, new)| new as i128 - old as i128))
+        }
+
+        // Register smart contract, lock&stake some amount
+        let smart_contract = MockSmartContract::wasm(1 as AccountId);
+        assert_register(1, &smart_contract);
+
+        let account = 2;
+        let amount = 300;
+        assert_lock(account, amount);
+        assert_stake(account, &smart_contract, amount);
+        assert!(observed_delta().is_none());
+
+        // 1. Stake becomes valid in the next era
+        advance_to_next_era();
+        assert_eq!(TOTAL_STAKE_CHANGED.with(|v| *v.borrow()), Some((0, amount)));
+        assert_eq!(observed_delta(), Some(amount as i128));
+
+        // 2. Additional stake is observed in the next era
+        let extra_amount = 100;
+        assert_lock(account, extra_amount);
+        assert_stake(account, &smart_contract, extra_amount);
+        advance_to_next_era();
+        assert_eq!(
+            TOTAL_STAKE_CHANGED.with(|v| *v.borrow()),
+            Some((amount, amount + extra_amount))
+        );
+        assert_eq!(observed_delta(), Some(extra_amount as i128));
+
+        // 3. Stakes are reset with the new period
+        advance_to_next_period();
+        assert_eq!(
+            TOTAL_STAKE_CHANGED.with(|v| *v.borrow()),
+            Some((amount + extra_amount, 0))
+        );
+        assert_eq!(observed_delta(), Some(-((amount + extra_amount) as i128)));
+    })
+}
+
 #[test]
 fn unregister_after_max_number_of_contracts_allows_register_again() {
     ExtBuilder::build().execute_with(|| {
@@ -16,10 +16,11 @@
 // You should have received a copy of the GNU General Public License
 // along with Astar. If not, see <http://www.gnu.org/licenses/>.
 
-use astar_primitives::Balance;
+use astar_primitives::{dapp_staking::SmartContract, Balance};
 use frame_support::assert_ok;
 use sp_arithmetic::fixed_point::FixedU64;
-use sp_runtime::Permill;
+use sp_runtime::{Perbill, Permill};
+use sp_std::cmp::Ordering;
 
 use crate::*;
 
@@ -168,6 +169,107 @@ fn dapp_info_basic_checks() {
     assert_eq!(*dapp_info.reward_beneficiary(), beneficiary);
 }
 
+#[test]
+fn dapp_info_with_beneficiary_works() {
+    let owner = 1;
+    let dapp_info = DAppInfo {
+        owner,
+        id: 7,
+        reward_beneficiary: None,
+    };
+
+    // None -> Some
+    let (dapp_info, old_beneficiary) = dapp_info.with_beneficiary(Some(3));
+    assert!(old_beneficiary.is_none());
+    assert_eq!(dapp_info.reward_beneficiary, Some(3));
+    assert_eq!(*dapp_info.reward_beneficiary(), 3);
+
+    // Some -> Some
+    let (dapp_info, old_beneficiary) = dapp_info.with_beneficiary(Some(5));
+    assert_eq!(old_beneficiary, Some(3));
+    assert_eq!(dapp_info.reward_beneficiary, Some(5));
+
+    // Some -> None
+    let (dapp_info, old_beneficiary) = dapp_info.with_beneficiary(None);
+    assert_eq!(old_beneficiary, Some(5));
+    assert!(dapp_info.reward_beneficiary.is_none());
+    assert_eq!(*dapp_info.reward_beneficiary(), owner);
+
+    // Other fields are unchanged
+    assert_eq!(dapp_info.owner, owner);
+    assert_eq!(dapp_info.id, 7);
+}
+
+#[test]
+fn registered_contracts_basic_checks() {
+    get_u32_type!(MaxContracts, 3);
+    let mut registered_contracts =
+        RegisteredContracts::<SmartContract<u32>, u32, MaxContracts>::new();
+    assert!(registered_contracts.is_empty());
+
+    let dapp_info = |id: DAppId| DAppInfo {
+        owner: 1,
+        id,
+        reward_beneficiary: None,
+    };
+
+    // Register a few contracts
+    for id in 0..3 {
+        assert_ok!(registered_contracts.try_register(SmartContract::Wasm(id.into()), dapp_info(id)));
+    }
+    assert_eq!(registered_contracts.len(), 3);
+    assert_eq!(
+        registered_contracts.get(1),
+        Some(&(SmartContract::Wasm(1), dapp_info(1)))
+    );
+
+    // Successful unregistration
+    assert_eq!(
+        registered_contracts.unregister(1),
+        Some((SmartContract::Wasm(1), dapp_info(1)))
+    );
+    assert_eq!(registered_contracts.len(), 2);
+    assert!(registered_contracts.get(1).is_none());
+    assert!(
+        registered_contracts.unregister(1).is_none(),
+        "Cannot unregister the same dApp twice."
+    );
+}
+
+#[test]
+fn registered_contracts_invariants_are_enforced() {
+    get_u32_type!(MaxContracts, 2);
+    let mut registered_contracts =
+        RegisteredContracts::<SmartContract<u32>, u32, MaxContracts>::new();
+
+    let dapp_info = |id: DAppId| DAppInfo {
+        owner: 1,
+        id,
+        reward_beneficiary: None,
+    };
+    assert_ok!(registered_contracts.try_register(SmartContract::Wasm(1), dapp_info(1)));
+
+    // Duplicate dApp Id is rejected
+    assert_eq!(
+        registered_contracts.try_register(SmartContract::Wasm(2), dapp_info(1)),
+        Err(())
+    );
+    // Duplicate contract is rejected
+    assert_eq!(
+        registered_contracts.try_register(SmartContract::Wasm(1), dapp_info(2)),
+        Err(())
+    );
+    assert_eq!(registered_contracts.len(), 1);
+
+    // Full list rejects new entries
+    assert_ok!(registered_contracts.try_register(SmartContract::Wasm(2), dapp_info(2)));
+    assert_eq!(
+        registered_contracts.try_register(SmartContract::Wasm(3), dapp_info(3)),
+        Err(())
+    );
+    assert_eq!(registered_contracts.len(), 2);
+}
+
 #[test]
 fn unlocking_chunk_basic_check() {
     // Sanity check
@@ -1120,15 +1222,233 @@ fn account_ledger_claim_unlocked_works() {
     assert_ok!(acc_ledger.add_unlocking_chunk(amount2, block2));
     assert_ok!(acc_ledger.add_unlocking_chunk(amount3, block3));
 
-    // Only claim 1 chunk
+    // Only claim 1 chunk, remaining chunks keep their order
     assert_eq!(acc_ledger.claim_unlocked(block1 + 1), amount1);
-    assert_eq!(acc_ledger.unlocking.len(), 2);
+    assert_eq!(
+        acc_ledger.unlocking.to_vec(),
+        vec![
+            UnlockingChunk {
+                amount: amount2,
+                unlock_block: block2,
+            },
+            UnlockingChunk {
+                amount: amount3,
+                unlock_block: block3,
+            }
+        ]
+    );
 
     // Claim remaining two chunks
     assert_eq!(acc_ledger.claim_unlocked(block3 + 1), amount2 + amount3);
     assert!(acc_ledger.unlocking.is_empty());
 }
 
+#[test]
+fn account_ledger_add_stake_works() {
+    get_u32_type!(UnlockingDummy, 5);
+    let mut acc_ledger = AccountLedger::<UnlockingDummy>::default();
+    acc_ledger.add_lock_amount(100);
+
+    // Valid adds are routed to the matching component
+    assert_ok!(acc_ledger.add_stake(30, Subperiod::Voting));
+    assert_ok!(acc_ledger.add_stake(20, Subperiod::BuildAndEarn));
+    assert_eq!(acc_ledger.staked.voting, 30);
+    assert_eq!(acc_ledger.staked.build_and_earn, 20);
+
+    // Exactly up to the locked amount is fine
+    assert_ok!(acc_ledger.add_stake(50, Subperiod::BuildAndEarn));
+    assert_eq!(acc_ledger.staked.total(), acc_ledger.active_locked_amount());
+
+    // Future entry is preferred, if it exists
+    acc_ledger.staked_future = Some(StakeAmount {
+        voting: 10,
+        ..Default::default()
+    });
+    assert_ok!(acc_ledger.add_stake(15, Subperiod::Voting));
+    assert_eq!(acc_ledger.staked_future.unwrap().voting, 25);
+    assert_eq!(acc_ledger.staked.voting, 30);
+}
+
+#[test]
+fn account_ledger_add_stake_fails() {
+    get_u32_type!(UnlockingDummy, 5);
+    let mut acc_ledger = AccountLedger::<UnlockingDummy>::default();
+    acc_ledger.add_lock_amount(100);
+
+    // Exceeding the locked amount
+    assert_ok!(acc_ledger.add_stake(60, Subperiod::Voting));
+    assert_eq!(acc_ledger.add_stake(41, Subperiod::BuildAndEarn), Err(()));
+    assert_eq!(acc_ledger.staked.total(), 60);
+
+    // Overflow
+    acc_ledger.locked = Balance::MAX;
+    acc_ledger.staked.build_and_earn = Balance::MAX - 60;
+    assert_eq!(acc_ledger.add_stake(1, Subperiod::Voting), Err(()));
+    assert_eq!(acc_ledger.staked.voting, 60);
+}
+
+#[test]
+fn account_ledger_period_snapshot_and_reset_works() {
+    get_u32_type!(UnlockingDummy, 5);
+    let mut acc_ledger = AccountLedger::<UnlockingDummy>::default();
+
+    // Sanity check scenario
+    assert_eq!(acc_ledger.period_snapshot(), PeriodStakeSnapshot::default());
+    acc_ledger.apply_period_reset();
+    assert!(acc_ledger.staked.is_empty());
+    assert!(acc_ledger.staked_future.is_none());
+
+    // Snapshot reflects the pre-reset values
+    acc_ledger.add_lock_amount(100);
+    acc_ledger.staked = StakeAmount {
+        voting: 30,
+        build_and_earn: 20,
+        era: 5,
+        period: 2,
+    };
+    let snapshot = acc_ledger.period_snapshot();
+    assert_eq!(
+        snapshot,
+        PeriodStakeSnapshot {
+            voting: 30,
+            build_and_earn: 20,
+            locked: 100,
+        }
+    );
+
+    // Reset only clears the `Build&Earn` component
+    acc_ledger.apply_period_reset();
+    assert_eq!(acc_ledger.staked.voting, 30);
+    assert!(acc_ledger.staked.build_and_earn.is_zero());
+    assert_eq!(acc_ledger.staked.era, 5);
+    assert_eq!(acc_ledger.active_locked_amount(), 100);
+    assert_eq!(
+        acc_ledger.period_snapshot(),
+        PeriodStakeSnapshot {
+            build_and_earn: 0,
+            ..snapshot
+        }
+    );
+
+    // Future entry is used for the snapshot & reset as well
+    acc_ledger.staked_future = Some(StakeAmount {
+        voting: 30,
+        build_and_earn: 15,
+        era: 6,
+        period: 2,
+    });
+    assert_eq!(acc_ledger.period_snapshot().build_and_earn, 15);
+    acc_ledger.apply_period_reset();
+    assert_eq!(
+        acc_ledger.staked_future,
+        Some(StakeAmount {
+            voting: 30,
+            build_and_earn: 0,
+            era: 6,
+            period: 2,
+        })
+    );
+
+    // Entries without any `Voting` stake are cleaned up
+    acc_ledger.staked = StakeAmount {
+        voting: 0,
+        build_and_earn: 20,
+        era: 5,
+        period: 2,
+    };
+    acc_ledger.staked_future = Some(StakeAmount {
+        voting: 0,
+        build_and_earn: 25,
+        era: 6,
+        period: 2,
+    });
+    acc_ledger.apply_period_reset();
+    assert!(acc_ledger.staked.is_empty());
+    assert!(acc_ledger.staked_future.is_none());
+    assert_eq!(
+        acc_ledger.period_snapshot(),
+        PeriodStakeSnapshot {
+            locked: 100,
+            ..Default::default()
+        }
+    );
+}
+
+#[test]
+fn account_ledger_start_unlock_works() {
+    get_u32_type!(UnlockingDummy, 5);
+    let mut acc_ledger = AccountLedger::<UnlockingDummy>::default();
+
+    acc_ledger.add_lock_amount(100);
+    acc_ledger.add_lock_amount(20);
+    assert_eq!(acc_ledger.active_locked_amount(), 120);
+
+    // Stake part of the locked amount
+    acc_ledger.staked = StakeAmount {
+        voting: 50,
+        build_and_earn: 0,
+        era: 1,
+        period: 1,
+    };
+
+    // Valid unlock
+    assert_ok!(acc_ledger.start_unlock(30, 10, 1));
+    assert_eq!(acc_ledger.active_locked_amount(), 90);
+    assert_eq!(acc_ledger.unlocking_amount(), 30);
+    assert_eq!(
+        acc_ledger.unlocking.to_vec(),
+        vec![UnlockingChunk {
+            amount: 30,
+            unlock_block: 10,
+        }]
+    );
+
+    // Exactly all of the stakeable funds can be unlocked
+    assert_ok!(acc_ledger.start_unlock(40, 10, 1));
+    assert_eq!(acc_ledger.active_locked_amount(), 50);
+    assert_eq!(acc_ledger.unlocking_amount(), 70);
+    assert_eq!(acc_ledger.total_locked_amount(), 120);
+
+    // Stake from the past period doesn't prevent unlocking
+    assert_ok!(acc_ledger.start_unlock(50, 10, 2));
+    assert!(acc_ledger.active_locked_amount().is_zero());
+    assert_eq!(acc_ledger.unlocking_amount(), 120);
+}
+
+#[test]
+fn account_ledger_start_unlock_fails() {
+    get_u32_type!(UnlockingDummy, 2);
+    let mut acc_ledger = AccountLedger::<UnlockingDummy>::default();
+    acc_ledger.add_lock_amount(100);
+    acc_ledger.staked = StakeAmount {
+        voting: 30,
+        build_and_earn: 10,
+        era: 1,
+        period: 1,
+    };
+
+    // Over-unlock, exceeding the unlockable funds
+    assert_eq!(acc_ledger.start_unlock(61, 10, 1), Err(()));
+    assert_eq!(acc_ledger.active_locked_amount(), 100);
+    assert!(acc_ledger.unlocking.is_empty());
+
+    // Future stake entry is taken into account
+    acc_ledger.staked_future = Some(StakeAmount {
+        voting: 30,
+        build_and_earn: 20,
+        era: 2,
+        period: 1,
+    });
+    assert_eq!(acc_ledger.start_unlock(51, 10, 1), Err(()));
+
+    // No more capacity for unlocking chunks
+    assert_ok!(acc_ledger.start_unlock(10, 10, 1));
+    assert_ok!(acc_ledger.start_unlock(10, 11, 1));
+    assert_eq!(acc_ledger.start_unlock(10, 12, 1), Err(()));
+    assert_eq!(acc_ledger.active_locked_amount(), 80);
+    assert_eq!(acc_ledger.unlocking_amount(), 20);
+}
+
 #[test]
 fn account_ledger_consume_unlocking_chunks_works() {
     get_u32_type!(UnlockingDummy, 5);
@@ -1185,6 +1505,83 @@ fn account_ledger_expired_cleanup_works() {
     assert!(acc_ledger.staked_future.is_none());
 }
 
+#[test]
+fn account_ledger_unstake_works() {
+    get_u32_type!(UnlockingDummy, 5);
+    let mut init_acc_ledger = AccountLedger::<UnlockingDummy>::default();
+    init_acc_ledger.add_lock_amount(100);
+    init_acc_ledger.staked = StakeAmount {
+        voting: 20,
+        build_and_earn: 10,
+        era: 1,
+        period: 1,
+    };
+
+    assert!(init_acc_ledger.can_unstake(0));
+    assert!(init_acc_ledger.can_unstake(30));
+    assert!(!init_acc_ledger.can_unstake(31));
+
+    // Valid unstake, only touches build&earn stake
+    let mut acc_ledger = init_acc_ledger.clone();
+    assert_ok!(acc_ledger.unstake(4));
+    assert_eq!(acc_ledger.staked.build_and_earn, 6);
+    assert_eq!(acc_ledger.staked.voting, 20);
+
+    // Build&earn stake is drained first, then voting
+    assert_ok!(acc_ledger.unstake(10));
+    assert!(acc_ledger.staked.build_and_earn.is_zero());
+    assert_eq!(acc_ledger.staked.voting, 16);
+
+    // Unstake everything, locked amount is unaffected
+    assert_ok!(acc_ledger.unstake(16));
+    assert!(acc_ledger.staked.is_empty());
+    assert_eq!(acc_ledger.active_locked_amount(), 100);
+
+    // Future entry is preferred, if it exists
+    let mut acc_ledger = init_acc_ledger.clone();
+    acc_ledger.staked_future = Some(StakeAmount {
+        voting: 40,
+        build_and_earn: 5,
+        ..Default::default()
+    });
+    assert!(acc_ledger.can_unstake(45));
+    assert_ok!(acc_ledger.unstake(15));
+    assert_eq!(
+        acc_ledger
+            .staked_future
+            .map(|s| (s.voting, s.build_and_earn)),
+        Some((30, 0))
+    );
+    assert_eq!(acc_ledger.staked, init_acc_ledger.staked);
+}
+
+#[test]
+fn account_ledger_unstake_fails() {
+    get_u32_type!(UnlockingDummy, 5);
+    let mut acc_ledger = AccountLedger::<UnlockingDummy>::default();
+    acc_ledger.add_lock_amount(100);
+    acc_ledger.staked = StakeAmount {
+        voting: 20,
+        build_and_earn: 10,
+        era: 1,
+        period: 1,
+    };
+
+    // Overshoot fails and changes nothing
+    let init_acc_ledger = acc_ledger.clone();
+    assert_eq!(acc_ledger.unstake(31), Err(()));
+    assert_eq!(acc_ledger, init_acc_ledger);
+
+    // Only the latest entry is considered
+    acc_ledger.staked_future = Some(StakeAmount {
+        voting: 5,
+        ..Default::default()
+    });
+    assert!(!acc_ledger.can_unstake(6));
+    assert_eq!(acc_ledger.unstake(6), Err(()));
+    assert_eq!(acc_ledger.staked_future.map(|s| s.voting), Some(5));
+}
+
 #[test]
 fn account_ledger_claim_up_to_era_only_staked_without_cleanup_works() {
     get_u32_type!(UnlockingDummy, 5);
@@ -2027,6 +2424,240 @@ fn stake_amount_works() {
     assert!(stake_amount.for_type(Subperiod::BuildAndEarn).is_zero());
 }
 
+#[test]
+fn stake_amount_serde_round_trip_works() {
+    // Small values
+    let stake_amount = StakeAmount {
+        voting: 17,
+        build_and_earn: 23,
+        era: 5,
+        period: 2,
+    };
+    let json = serde_json::to_string(&stake_amount).expect("Must serialize.");
+    assert_eq!(
+        json,
+        r#"{"voting":"17","build_and_earn":"23","era":5,"period":2}"#
+    );
+    assert_eq!(
+        serde_json::from_str::<StakeAmount>(&json).expect("Must deserialize."),
+        stake_amount
+    );
+
+    // Values beyond the safe JSON integer range
+    let stake_amount = StakeAmount {
+        voting: Balance::MAX,
+        build_and_earn: Balance::MAX - 1,
+        ..stake_amount
+    };
+    let json = serde_json::to_string(&stake_amount).expect("Must serialize.");
+    assert!(json.contains(&Balance::MAX.to_string()));
+    assert_eq!(
+        serde_json::from_str::<StakeAmount>(&json).expect("Must deserialize."),
+        stake_amount
+    );
+
+    // Plain numbers are accepted too, up to `u64::MAX`
+    assert_eq!(
+        serde_json::from_str::<StakeAmount>(
+            r#"{"voting":17,"build_and_earn":"23","era":5,"period":2}"#
+        )
+        .expect("Must deserialize."),
+        StakeAmount {
+            voting: 17,
+            build_and_earn: 23,
+            era: 5,
+            period: 2,
+        }
+    );
+
+    // Invalid values are rejected
+    assert!(serde_json::from_str::<StakeAmount>(
+        r#"{"voting":"-1","build_and_earn":"23","era":5,"period":2}"#
+    )
+    .is_err());
+    assert!(serde_json::from_str::<StakeAmount>(
+        r#"{"voting":-1,"build_and_earn":"23","era":5,"period":2}"#
+    )
+    .is_err());
+
+    // Plain numbers beyond `u64::MAX` are rejected, only strings can represent them
+    assert_eq!(
+        serde_json::from_str::<StakeAmount>(&format!(
+            r#"{{"voting":{},"build_and_earn":"23","era":5,"period":2}}"#,
+            u64::MAX
+        ))
+        .expect("Must deserialize.")
+        .voting,
+        Balance::from(u64::MAX)
+    );
+    assert!(serde_json::from_str::<StakeAmount>(&format!(
+        r#"{{"voting":{},"build_and_earn":"23","era":5,"period":2}}"#,
+        Balance::from(u64::MAX) + 1
+    ))
+    .is_err());
+}
+
+#[test]
+fn stake_amount_weighted_average_works() {
+    // No samples
+    assert_eq!(StakeAmount::weighted_average(&[]), StakeAmount::default());
+
+    let stake_amount_1 = StakeAmount {
+        voting: 100,
+        build_and_earn: 40,
+        era: 3,
+        period: 1,
+    };
+    let stake_amount_2 = StakeAmount {
+        voting: 10,
+        build_and_earn: 70,
+        era: 7,
+        period: 1,
+    };
+
+    // Zero weights only
+    assert_eq!(
+        StakeAmount::weighted_average(&[(0, stake_amount_1), (0, stake_amount_2)]),
+        StakeAmount::default()
+    );
+
+    // Uniform samples
+    let average = StakeAmount::weighted_average(&[(2, stake_amount_1), (2, stake_amount_2)]);
+    assert_eq!(average.voting, (100 + 10) / 2);
+    assert_eq!(average.build_and_earn, (40 + 70) / 2);
+    assert_eq!(average.era, stake_amount_2.era);
+    assert_eq!(average.period, stake_amount_2.period);
+
+    // Single sample
+    assert_eq!(
+        StakeAmount::weighted_average(&[(5, stake_amount_1)]),
+        stake_amount_1
+    );
+
+    // Non-uniform samples, rounded down
+    let average = StakeAmount::weighted_average(&[(1, stake_amount_1), (2, stake_amount_2)]);
+    assert_eq!(average.voting, (100 + 10 * 2) / 3);
+    assert_eq!(average.build_and_earn, (40 + 70 * 2) / 3);
+}
+
+#[test]
+fn stake_amount_roll_to_next_period_works() {
+    let stake_amount = StakeAmount {
+        voting: 17,
+        build_and_earn: 23,
+        era: 5,
+        period: 2,
+    };
+
+    // Voting stake survives, build&earn stake is cleared
+    let rolled = stake_amount.roll_to_next_period();
+    assert_eq!(rolled.for_type(Subperiod::Voting), stake_amount.voting);
+    assert!(rolled.for_type(Subperiod::BuildAndEarn).is_zero());
+    assert_eq!(rolled.total(), stake_amount.voting);
+    assert_eq!(rolled.era, stake_amount.era);
+    assert_eq!(rolled.period, stake_amount.period + 1);
+
+    // Rolling again keeps the voting stake
+    let rolled_twice = rolled.roll_to_next_period();
+    assert_eq!(rolled_twice.voting, stake_amount.voting);
+    assert_eq!(rolled_twice.period, stake_amount.period + 2);
+
+    // Only build&earn stake means nothing is carried over
+    let stake_amount = StakeAmount {
+        voting: 0,
+        ..stake_amount
+    };
+    assert!(stake_amount.roll_to_next_period().is_empty());
+}
+
+#[test]
+fn stake_amount_scale_works() {
+    let stake_amount = StakeAmount {
+        voting: 101,
+        build_and_earn: 40,
+        era: 5,
+        period: 2,
+    };
+
+    // 50% scale, rounded down
+    let scaled = stake_amount.scale(Perbill::from_percent(50));
+    assert_eq!(scaled.voting, 50);
+    assert_eq!(scaled.build_and_earn, 20);
+    assert_eq!(scaled.era, stake_amount.era);
+    assert_eq!(scaled.period, stake_amount.period);
+
+    // Full scale is a noop, zero scale clears the stake
+    assert_eq!(stake_amount.scale(Perbill::from_percent(100)), stake_amount);
+    assert!(stake_amount.scale(Perbill::from_percent(0)).is_empty());
+}
+
+#[test]
+fn stake_amount_rescale_denomination_works() {
+    let stake_amount = StakeAmount {
+        voting: 101,
+        build_and_earn: 40,
+        era: 5,
+        period: 2,
+    };
+
+    // Integer 10x
+    let rescaled = stake_amount.rescale_denomination(10);
+    assert_eq!(rescaled.voting, 1010);
+    assert_eq!(rescaled.build_and_earn, 400);
+    assert_eq!(rescaled.era, stake_amount.era);
+    assert_eq!(rescaled.period, stake_amount.period);
+
+    // Saturation near the max balance
+    let stake_amount = StakeAmount {
+        voting: Balance::MAX / 5,
+        build_and_earn: Balance::MAX / 20,
+        ..stake_amount
+    };
+    let rescaled = stake_amount.rescale_denomination(10);
+    assert_eq!(rescaled.voting, Balance::MAX);
+    assert_eq!(rescaled.build_and_earn, Balance::MAX / 20 * 10);
+}
+
+#[test]
+fn cmp_by_total_works() {
+    let stake_amount = |voting, build_and_earn, era| StakeAmount {
+        voting,
+        build_and_earn,
+        era,
+        period: 1,
+    };
+
+    // Total stake has precedence over the voting component
+    let smaller = stake_amount(50, 10, 1);
+    let larger = stake_amount(0, 100, 1);
+    assert_eq!(cmp_by_total(&smaller, &larger), Ordering::Less);
+    assert_eq!(cmp_by_total(&larger, &smaller), Ordering::Greater);
+
+    // Equal totals, larger voting component ranks higher
+    let more_voting = stake_amount(70, 30, 1);
+    let less_voting = stake_amount(30, 70, 1);
+    assert_eq!(cmp_by_total(&more_voting, &less_voting), Ordering::Greater);
+    assert_eq!(cmp_by_total(&less_voting, &more_voting), Ordering::Less);
+
+    // Equal components, era & period are ignored
+    let other_era = StakeAmount {
+        era: 7,
+        period: 2,
+        ..more_voting
+    };
+    assert_eq!(cmp_by_total(&more_voting, &other_era), Ordering::Equal);
+
+    // Ranking is reproducible, regardless of the input order
+    let expected = vec![larger, more_voting, less_voting, smaller];
+    for mut stakes in [
+        vec![smaller, less_voting, more_voting, larger],
+        vec![less_voting, larger, smaller, more_voting],
+    ] {
+        stakes.sort_by(|a, b| cmp_by_total(b, a));
+        assert_eq!(stakes, expected);
+    }
+}
+
 #[test]
 fn singular_staking_info_basics_are_ok() {
     let period_number = 3;
@@ -2189,6 +2820,137 @@ fn singular_staking_info_unstake_during_bep_is_ok() {
     assert_eq!(staking_info.era(), era_2);
 }
 
+#[test]
+fn singular_staking_info_restake_preserving_bonus_works() {
+    let period_number = 3;
+    let era = 2;
+    let amount = 20;
+
+    let mut from = SingularStakingInfo::new(period_number, Subperiod::Voting);
+    from.stake(amount, era, Subperiod::Voting);
+    let mut to = SingularStakingInfo::new(period_number, Subperiod::Voting);
+
+    // Partial transfer, both entries remain eligible
+    let partial_amount = 5;
+    assert_eq!(
+        SingularStakingInfo::restake_preserving_bonus(&mut from, &mut to, partial_amount, era),
+        Ok(())
+    );
+    assert_eq!(
+        from.staked_amount(Subperiod::Voting),
+        amount - partial_amount
+    );
+    assert_eq!(to.staked_amount(Subperiod::Voting), partial_amount);
+    assert!(from.is_loyal());
+    assert!(to.is_loyal());
+    assert_eq!(to.era(), era + 1);
+
+    // Full transfer, eligibility moves to the target entry
+    let remaining_amount = from.total_staked_amount();
+    assert_eq!(
+        SingularStakingInfo::restake_preserving_bonus(&mut from, &mut to, remaining_amount, era),
+        Ok(())
+    );
+    assert!(from.is_empty());
+    assert!(!from.is_loyal());
+    assert_eq!(to.staked_amount(Subperiod::Voting), amount);
+    assert!(to.is_loyal());
+
+    // Eligibility cannot be gained by restaking
+    let mut non_loyal = SingularStakingInfo::new(period_number, Subperiod::BuildAndEarn);
+    assert_eq!(
+        SingularStakingInfo::restake_preserving_bonus(&mut to, &mut non_loyal, 1, era),
+        Ok(())
+    );
+    assert!(to.is_loyal());
+    assert!(!non_loyal.is_loyal());
+}
+
+#[test]
+fn singular_staking_info_restake_preserving_bonus_fails() {
+    let period_number = 3;
+    let era = 2;
+
+    let mut from = SingularStakingInfo::new(period_number, Subperiod::Voting);
+    from.stake(10, era, Subperiod::Voting);
+    from.stake(15, era, Subperiod::BuildAndEarn);
+    let init_from = from;
+
+    // Only voting stake can be restaked
+    let mut to = SingularStakingInfo::new(period_number, Subperiod::Voting);
+    let init_to = to;
+    assert_eq!(
+        SingularStakingInfo::restake_preserving_bonus(&mut from, &mut to, 11, era),
+        Err(())
+    );
+    assert_eq!(from, init_from);
+    assert_eq!(to, init_to);
+
+    // Periods must match
+    let mut to = SingularStakingInfo::new(period_number + 1, Subperiod::Voting);
+    let init_to = to;
+    assert_eq!(
+        SingularStakingInfo::restake_preserving_bonus(&mut from, &mut to, 5, era),
+        Err(())
+    );
+    assert_eq!(from, init_from);
+    assert_eq!(to, init_to);
+}
+
+#[test]
+fn singular_staking_info_derive_bonus_status_works() {
+    let stake = StakeAmount {
+        voting: 50,
+        build_and_earn: 20,
+        era: 4,
+        period: 2,
+    };
+
+    // Staker maintained the stake through the voting subperiod
+    let bonus_status = SingularStakingInfo::derive_bonus_status(&stake, true);
+    assert!(bonus_status.is_loyal());
+    assert_eq!(bonus_status.staked_amount(Subperiod::Voting), stake.voting);
+    assert!(bonus_status
+        .staked_amount(Subperiod::BuildAndEarn)
+        .is_zero());
+    assert_eq!(bonus_status.era(), stake.era);
+    assert_eq!(bonus_status.period_number(), stake.period);
+
+    // Staker withdrew during the voting subperiod
+    let bonus_status = SingularStakingInfo::derive_bonus_status(&stake, false);
+    assert!(!bonus_status.is_loyal());
+    assert_eq!(bonus_status.staked_amount(Subperiod::Voting), stake.voting);
+}
+
+#[test]
+fn bonus_eligible_from_status_works() {
+    let stake = StakeAmount {
+        voting: 50,
+        build_and_earn: 20,
+        era: 4,
+        period: 2,
+    };
+    let loyal_status = SingularStakingInfo::derive_bonus_status(&stake, true);
+    let disloyal_status = SingularStakingInfo::derive_bonus_status(&stake, false);
+
+    // Full voting subperiod required
+    assert!(bonus_eligible_from_status(&loyal_status, true, true));
+    assert!(!bonus_eligible_from_status(&loyal_status, false, true));
+    assert!(!bonus_eligible_from_status(&disloyal_status, true, true));
+    assert!(!bonus_eligible_from_status(&disloyal_status, false, true));
+
+    // Partial voting subperiod allowed
+    assert!(bonus_eligible_from_status(&loyal_status, true, false));
+    assert!(bonus_eligible_from_status(&loyal_status, false, false));
+    assert!(bonus_eligible_from_status(&disloyal_status, false, false));
+
+    // Voting stake is required regardless of the policy
+    let no_voting_status =
+        SingularStakingInfo::derive_bonus_status(&StakeAmount { voting: 0, ..stake }, true);
+    assert!(!bonus_eligible_from_status(&no_voting_status, true, true));
+    assert!(!bonus_eligible_from_status(&no_voting_status, true, false));
+}
+
 #[test]
 fn contract_stake_amount_basic_get_checks_work() {
     // Sanity checks for empty struct
@@ -2693,6 +3455,136 @@ fn tier_params_check_is_ok() {
     assert!(!new_params.is_valid());
 }
 
+#[test]
+fn tier_params_dapp_pool_by_tier_works() {
+    get_u32_type!(TiersNum, 3);
+    let mut params = TierParameters::<TiersNum> {
+        reward_portion: BoundedVec::try_from(vec![
+            Permill::from_percent(50),
+            Permill::from_percent(30),
+            Permill::from_percent(20),
+        ])
+        .unwrap(),
+        slot_distribution: BoundedVec::try_from(vec![
+            Permill::from_percent(10),
+            Permill::from_percent(20),
+            Permill::from_percent(70),
+        ])
+        .unwrap(),
+        tier_thresholds: BoundedVec::try_from(vec![
+            TierThreshold::FixedTvlAmount { amount: 100 },
+            TierThreshold::FixedTvlAmount { amount: 50 },
+            TierThreshold::FixedTvlAmount { amount: 10 },
+        ])
+        .unwrap(),
+    };
+    assert!(params.is_valid());
+
+    // Exact split
+    assert_eq!(
+        params.dapp_pool_by_tier(1000),
+        vec![(0, 500), (1, 300), (2, 200)]
+    );
+
+    // Rounding dust goes to the top tier
+    let pool = 1003;
+    let allocations = params.dapp_pool_by_tier(pool);
+    assert_eq!(allocations, vec![(0, 503), (1, 300), (2, 200)]);
+    assert_eq!(allocations.iter().map(|(_, x)| x).sum::<Balance>(), pool);
+
+    // Portions not covering the whole pool, remainder goes to the top tier
+    params.reward_portion = BoundedVec::try_from(vec![
+        Permill::from_percent(40),
+        Permill::from_percent(30),
+        Permill::from_percent(10),
+    ])
+    .unwrap();
+    for pool in [0, 1, 7, 999, 1_000_000_000_000_000_007] {
+        let allocations = params.dapp_pool_by_tier(pool);
+        assert_eq!(allocations.len(), 3);
+        assert_eq!(allocations.iter().map(|(_, x)| x).sum::<Balance>(), pool);
+    }
+    assert_eq!(
+        params.dapp_pool_by_tier(1000),
+        vec![(0, 600), (1, 300), (2, 100)]
+    );
+
+    // No tiers, nothing to allocate
+    assert!(TierParameters::<TiersNum>::default()
+        .dapp_pool_by_tier(1000)
+        .is_empty());
+}
+
+#[test]
+fn tier_params_normalize_tier_rewards_works() {
+    get_u32_type!(TiersNum, 3);
+    let reward_portion = |percents: [u32; 3]| {
+        BoundedVec::<Permill, TiersNum>::try_from(
+            percents
+                .into_iter()
+                .map(Permill::from_percent)
+                .collect::<Vec<_>>(),
+        )
+        .unwrap()
+    };
+    let portions_sum = |params: &TierParameters<TiersNum>| {
+        params
+            .reward_portion
+            .iter()
+            .map(|portion| portion.deconstruct())
+            .sum::<u32>()
+    };
+
+    let mut params = TierParameters::<TiersNum> {
+        reward_portion: reward_portion([50, 30, 10]),
+        ..Default::default()
+    };
+
+    // Portions below 100% are scaled up, with the rounding remainder assigned to the top tier
+    params.normalize_tier_rewards();
+    assert_eq!(
+        params.reward_portion.to_vec(),
+        vec![
+            Permill::from_parts(555_556),
+            Permill::from_parts(333_333),
+            Permill::from_parts(111_111),
+        ]
+    );
+    assert_eq!(portions_sum(&params), Permill::one().deconstruct());
+
+    // Already normalized portions are unchanged
+    let normalized = params.reward_portion.clone();
+    params.normalize_tier_rewards();
+    assert_eq!(params.reward_portion, normalized);
+
+    // Portions above 100% are scaled down
+    params.reward_portion = reward_portion([60, 30, 30]);
+    params.normalize_tier_rewards();
+    assert_eq!(params.reward_portion, reward_portion([50, 25, 25]));
+    assert_eq!(portions_sum(&params), Permill::one().deconstruct());
+
+    // Various portions always sum up to exactly 100%
+    for percents in [[1, 1, 1], [7, 0, 13], [99, 99, 99], [0, 0, 1], [33, 33, 33]] {
+        params.reward_portion = reward_portion(percents);
+        params.normalize_tier_rewards();
+        assert_eq!(
+            portions_sum(&params),
+            Permill::one().deconstruct(),
+            "{:?}",
+            percents
+        );
+    }
+
+    // Undefined proportions, nothing is changed
+    params.reward_portion = reward_portion([0, 0, 0]);
+    params.normalize_tier_rewards();
+    assert_eq!(params.reward_portion, reward_portion([0, 0, 0]));
+
+    let mut no_tiers = TierParameters::<TiersNum>::default();
+    no_tiers.normalize_tier_rewards();
+    assert!(no_tiers.reward_portion.is_empty());
+}
+
 #[test]
 fn tier_configuration_basic_tests() {
     // TODO: this should be expanded & improved later
@@ -2752,6 +3644,106 @@ fn tier_configuration_basic_tests() {
     // TODO: expand tests, add more sanity checks (e.g. tier 3 requirement should never be lower than tier 4, etc.)
 }
 
+#[test]
+fn tier_configuration_scale_slots_works() {
+    get_u32_type!(TiersNum, 4);
+    let init_config = TiersConfiguration::<TiersNum> {
+        number_of_slots: 56,
+        slots_per_tier: BoundedVec::try_from(vec![1, 15, 40, 0]).unwrap(),
+        reward_portion: BoundedVec::try_from(vec![
+            Permill::from_percent(40),
+            Permill::from_percent(30),
+            Permill::from_percent(20),
+            Permill::from_percent(10),
+        ])
+        .unwrap(),
+        tier_thresholds: BoundedVec::try_from(vec![
+            TierThreshold::DynamicTvlAmount {
+                amount: 1000,
+                minimum_amount: 800,
+            },
+            TierThreshold::DynamicTvlAmount {
+                amount: 500,
+                minimum_amount: 350,
+            },
+            TierThreshold::DynamicTvlAmount {
+                amount: 100,
+                minimum_amount: 70,
+            },
+            TierThreshold::FixedTvlAmount { amount: 50 },
+        ])
+        .unwrap(),
+    };
+    assert!(init_config.is_valid());
+
+    // Scale down by 50%, rounding down but never below 1 for non-empty tiers
+    let scaled_config = init_config.scale_slots(FixedU64::from_rational(1, 2));
+    assert_eq!(scaled_config.slots_per_tier.to_vec(), vec![1, 7, 20, 0]);
+    assert_eq!(scaled_config.number_of_slots, 28);
+    assert_eq!(scaled_config.reward_portion, init_config.reward_portion);
+    assert_eq!(scaled_config.tier_thresholds, init_config.tier_thresholds);
+    assert!(scaled_config.is_valid());
+
+    // Scale up beyond the original capacity
+    let scaled_config = init_config.scale_slots(FixedU64::from_rational(5, 2));
+    assert_eq!(scaled_config.slots_per_tier.to_vec(), vec![2, 37, 100, 0]);
+    assert_eq!(scaled_config.number_of_slots, 139);
+    assert!(scaled_config.is_valid());
+
+    // Scaling to zero keeps a single slot in non-empty tiers
+    let scaled_config = init_config.scale_slots(FixedU64::from_rational(0, 1));
+    assert_eq!(scaled_config.slots_per_tier.to_vec(), vec![1, 1, 1, 0]);
+    assert_eq!(scaled_config.number_of_slots, 3);
+
+    // Identity
+    assert_eq!(
+        init_config.scale_slots(FixedU64::from_rational(1, 1)),
+        init_config
+    );
+}
+
+#[test]
+fn tier_configuration_tier_utilization_works() {
+    get_u32_type!(TiersNum, 4);
+    let config = TiersConfiguration::<TiersNum> {
+        number_of_slots: 16,
+        slots_per_tier: BoundedVec::try_from(vec![2, 4, 10, 0]).unwrap(),
+        reward_portion: BoundedVec::try_from(vec![Permill::from_percent(25); 4]).unwrap(),
+        tier_thresholds: BoundedVec::try_from(vec![
+            TierThreshold::FixedTvlAmount { amount: 10 };
+            4
+        ])
+        .unwrap(),
+    };
+
+    let utilization = config.tier_utilization(&[(0, 2), (1, 7), (2, 3), (3, 1)]);
+    assert_eq!(
+        utilization,
+        vec![
+            // Exactly full
+            (0, Perbill::one()),
+            // Over-assigned, capped
+            (1, Perbill::one()),
+            // Under-full
+            (2, Perbill::from_percent(30)),
+            // No capacity
+            (3, Perbill::zero()),
+        ]
+    );
+
+    // Missing entries are treated as empty tiers
+    let utilization = config.tier_utilization(&[(2, 5)]);
+    assert_eq!(
+        utilization,
+        vec![
+            (0, Perbill::zero()),
+            (1, Perbill::zero()),
+            (2, Perbill::from_percent(50)),
+            (3, Perbill::zero()),
+        ]
+    );
+}
+
 #[test]
 fn dapp_tier_rewards_basic_tests() {
     get_u32_type!(NumberOfDApps, 8);
@@ -31,7 +31,7 @@
 //!
 //! * `EraNumber` - numeric Id of an era.
 //! * `PeriodNumber` - numeric Id of a period.
-//! * `Subperiod` - an enum describing which subperiod is active in the current period.
+//! * `Subperiod` - an enum describing which subperiod is active in the current period, re-exported from `astar-primitives`.
 //! * `PeriodInfo` - contains information about the ongoing period, like period number, current subperiod and when will the current subperiod end.
 //! * `PeriodEndInfo` - contains information about a finished past period, like the final era of the period, total amount staked & bonus reward pool.
 //! * `ProtocolState` - contains the most general protocol state info: current era number, block when the era ends, ongoing period info, and whether protocol is in maintenance mode.
@@ -41,6 +41,7 @@
 //! * `DAppId` - a compact unique numeric Id of a dApp.
 //! * `DAppInfo` - contains general information about a dApp, like owner and reward beneficiary, Id and state.
 //! * `ContractStakeAmount` - contains information about how much is staked on a particular contract.
+//! * `RegisteredContracts` - bounded list of registered contracts and their dApp information, enforcing registration invariants.
 //!
 //! ## Staker Information
 //!
@@ -70,10 +71,12 @@ use serde::{Deserialize, Serialize};
 use sp_arithmetic::fixed_point::FixedU64;
 use sp_runtime::{
     traits::{CheckedAdd, UniqueSaturatedInto, Zero},
-    FixedPointNumber, Permill, Saturating,
+    FixedPointNumber, Perbill, Permill, Saturating,
 };
+use sp_std::cmp::Ordering;
 pub use sp_std::{collections::btree_map::BTreeMap, fmt::Debug, vec::Vec};
 
+pub use astar_primitives::dapp_staking::Subperiod;
 use astar_primitives::{
     dapp_staking::{DAppId, EraNumber, PeriodNumber, TierId},
     Balance, BlockNumber,
@@ -113,25 +116,6 @@ pub enum AccountLedgerError {
     InvalidIterator,
 }
 
-/// Distinct subperiods in dApp staking protocol.
-#[derive(Encode, Decode, MaxEncodedLen, Clone, Copy, Debug, PartialEq, Eq, TypeInfo)]
-pub enum Subperiod {
-    /// Subperiod during which the focus is on voting. No rewards are earned during this subperiod.
-    Voting,
-    /// Subperiod during which dApps and stakers earn rewards.
-    BuildAndEarn,
-}
-
-impl Subperiod {
-    /// Next subperiod, after `self`.
-    pub fn next(&self) -> Self {
-        match self {
-            Subperiod::Voting => Subperiod::BuildAndEarn,
-            Subperiod::BuildAndEarn => Subperiod::Voting,
-        }
-    }
-}
-
 /// Info about the ongoing period.
 #[derive(Encode, Decode, MaxEncodedLen, Clone, Copy, Debug, PartialEq, Eq, TypeInfo)]
 pub struct PeriodInfo {
@@ -268,6 +252,90 @@ impl<AccountId> DAppInfo<AccountId> {
             None => &self.owner,
         }
     }
+
+    /// Replace the reward beneficiary of this dApp with `new`.
+    ///
+    /// Returns the updated dApp info, together with the previous beneficiary, e.g. for event emission.
+    pub fn with_beneficiary(mut self, new: Option<AccountId>) -> (Self, Option<AccountId>) {
+        let old = sp_std::mem::replace(&mut self.reward_beneficiary, new);
+        (self, old)
+    }
+}
+
+/// Bounded list of registered smart contracts, together with their dApp information.
+///
+/// Ensures that neither a smart contract nor a dApp Id can be registered more than once,
+/// and that the maximum number of registered contracts is never exceeded.
+#[derive(
+    Encode,
+    Decode,
+    MaxEncodedLen,
+    RuntimeDebugNoBound,
+    PartialEqNoBound,
+    EqNoBound,
+    CloneNoBound,
+    TypeInfo,
+)]
+#[scale_info(skip_type_params(ML))]
+pub struct RegisteredContracts<
+    SmartContract: Parameter + MaxEncodedLen,
+    AccountId: Parameter + MaxEncodedLen,
+    ML: Get<u32>,
+>(BoundedVec<(SmartContract, DAppInfo<AccountId>), ML>);
+
+impl<SmartContract, AccountId, ML> RegisteredContracts<SmartContract, AccountId, ML>
+where
+    SmartContract: Parameter + MaxEncodedLen,
+    AccountId: Parameter + MaxEncodedLen,
+    ML: Get<u32>,
+{
+    /// Create new, empty, list of registered contracts.
+    pub fn new() -> Self {
+        Self(BoundedVec::default())
+    }
+
+    /// Number of registered contracts.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// `true` if no contract is registered, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Registered smart contract & dApp info for the specified dApp Id, if it exists.
+    pub fn get(&self, id: DAppId) -> Option<&(SmartContract, DAppInfo<AccountId>)> {
+        self.0.iter().find(|(_, info)| info.id == id)
+    }
+
+    /// Register the smart contract with the provided dApp info.
+    ///
+    /// Fails if either the smart contract or the dApp Id are already registered,
+    /// or if the list has no more capacity. In case of failure, the list is left unchanged.
+    pub fn try_register(
+        &mut self,
+        smart_contract: SmartContract,
+        info: DAppInfo<AccountId>,
+    ) -> Result<(), ()> {
+        if self
+            .0
+            .iter()
+            .any(|(contract, dapp_info)| *contract == smart_contract || dapp_info.id == info.id)
+        {
+            return Err(());
+        }
+
+        self.0.try_push((smart_contract, info)).map_err(|_| ())
+    }
+
+    /// Unregister the smart contract with the specified dApp Id.
+    ///
+    /// Returns the removed smart contract & dApp info, or `None` if no such dApp Id is registered.
+    pub fn unregister(&mut self, id: DAppId) -> Option<(SmartContract, DAppInfo<AccountId>)> {
+        let idx = self.0.iter().position(|(_, info)| info.id == id)?;
+        Some(self.0.remove(idx))
+    }
 }
 
 /// How much was unlocked in some block.
@@ -462,6 +530,29 @@ where
         Ok(())
     }
 
+    /// Moves the specified amount from the active locked amount into an unlocking chunk, unlocked at `unlock_block`.
+    ///
+    /// Only funds which aren't staked in `current_period` can be unlocked, as returned by [`Self::unlockable_amount`].
+    ///
+    /// Returns an error if the amount exceeds the unlockable funds, or if no more unlocking chunks can be added.
+    /// In case of an error, nothing is changed.
+    pub fn start_unlock(
+        &mut self,
+        amount: Balance,
+        unlock_block: BlockNumber,
+        current_period: PeriodNumber,
+    ) -> Result<(), ()> {
+        if amount > self.unlockable_amount(current_period) {
+            return Err(());
+        }
+
+        self.add_unlocking_chunk(amount, unlock_block)
+            .map_err(|_| ())?;
+        self.subtract_lock_amount(amount);
+
+        Ok(())
+    }
+
     /// Amount available for unlocking.
     pub fn unlockable_amount(&self, current_period: PeriodNumber) -> Balance {
         self.active_locked_amount()
@@ -469,6 +560,9 @@ where
     }
 
     /// Claims all of the fully unlocked chunks, and returns the total claimable amount.
+    ///
+    /// Matured chunks, i.e. the ones with `unlock_block <= current_block_number`, are removed, and the order of the remaining chunks is preserved.
+    /// Must only be called once the freed balance has been credited, since the matured chunks are lost afterwards.
     pub fn claim_unlocked(&mut self, current_block_number: BlockNumber) -> Balance {
         let mut total = Balance::zero();
 
@@ -608,6 +702,61 @@ where
         Ok(())
     }
 
+    /// Adds the specified amount to the latest stake entry, for the specified subperiod.
+    ///
+    /// Latest entry is the 'future' entry if it exists, otherwise the 'current' one.
+    /// Unlike [`Self::add_stake_amount`], no era or period bookkeeping is done.
+    ///
+    /// Returns an error if the new total staked amount would overflow, or exceed the active locked amount.
+    pub fn add_stake(&mut self, amount: Balance, subperiod: Subperiod) -> Result<(), ()> {
+        let locked = self.locked;
+        let stake_amount = match self.staked_future.as_mut() {
+            Some(stake_amount) => stake_amount,
+            None => &mut self.staked,
+        };
+
+        let new_total = stake_amount.total().checked_add(amount).ok_or(())?;
+        if new_total > locked {
+            return Err(());
+        }
+
+        stake_amount.add(amount, subperiod);
+        Ok(())
+    }
+
+    /// Snapshot of the staked components & the locked amount, e.g. for the bonus calculation at the period boundary.
+    ///
+    /// Stake is taken from the latest entry, i.e. the 'future' entry if it exists, otherwise the 'current' one.
+    pub fn period_snapshot(&self) -> PeriodStakeSnapshot {
+        let stake_amount = self.staked_future.unwrap_or(self.staked);
+
+        PeriodStakeSnapshot {
+            voting: stake_amount.voting,
+            build_and_earn: stake_amount.build_and_earn,
+            locked: self.locked,
+        }
+    }
+
+    /// Clear the `Build&Earn` stake from both stake entries, keeping the `Voting` stake intact.
+    ///
+    /// Entries left without any stake are cleaned up.
+    pub fn apply_period_reset(&mut self) {
+        self.staked.build_and_earn = Balance::zero();
+        if self.staked.is_empty() {
+            self.staked = Default::default();
+        }
+
+        if let Some(mut stake_amount) = self.staked_future {
+            stake_amount.build_and_earn = Balance::zero();
+
+            self.staked_future = if stake_amount.is_empty() {
+                None
+            } else {
+                Some(stake_amount)
+            };
+        }
+    }
+
     /// Subtracts the specified amount from the total staked amount, if possible.
     ///
     /// Unstake can only be called if the entry for the current era exists.
@@ -650,6 +799,34 @@ where
         Ok(())
     }
 
+    /// `true` if the specified `amount` can be unstaked from the latest stake entry, `false` otherwise.
+    ///
+    /// Latest entry is the 'future' entry if it exists, otherwise the 'current' one.
+    pub fn can_unstake(&self, amount: Balance) -> bool {
+        amount <= self.staked_future.unwrap_or(self.staked).total()
+    }
+
+    /// Subtracts the specified amount from the latest stake entry, if possible.
+    ///
+    /// Latest entry is the 'future' entry if it exists, otherwise the 'current' one.
+    /// Unlike [`Self::unstake_amount`], no era or period bookkeeping is done.
+    /// The amount is first subtracted from the build&earn stake, and any remainder from the voting stake.
+    ///
+    /// Returns an error if the amount exceeds the staked amount, in which case nothing is changed.
+    pub fn unstake(&mut self, amount: Balance) -> Result<(), ()> {
+        if !self.can_unstake(amount) {
+            return Err(());
+        }
+
+        let stake_amount = match self.staked_future.as_mut() {
+            Some(stake_amount) => stake_amount,
+            None => &mut self.staked,
+        };
+        stake_amount.subtract(amount, Subperiod::BuildAndEarn);
+
+        Ok(())
+    }
+
     /// Period for which account has staking information or `None` if no staking information exists.
     pub fn staked_period(&self) -> Option<PeriodNumber> {
         if self.staked.is_empty() {
@@ -745,6 +922,20 @@ where
     }
 }
 
+/// Snapshot of the account's staked components & locked amount, taken at the period boundary.
+#[derive(Encode, Decode, MaxEncodedLen, Copy, Clone, Debug, PartialEq, Eq, TypeInfo, Default)]
+pub struct PeriodStakeSnapshot {
+    /// Amount staked during the `Voting` subperiod.
+    #[codec(compact)]
+    pub voting: Balance,
+    /// Amount staked during the `Build&Earn` subperiod.
+    #[codec(compact)]
+    pub build_and_earn: Balance,
+    /// Active locked amount.
+    #[codec(compact)]
+    pub locked: Balance,
+}
+
 /// Helper internal struct for iterating over `(era, stake amount)` pairs.
 ///
 /// Due to how `AccountLedger` is implemented, few scenarios are possible when claiming rewards:
@@ -814,14 +1005,62 @@ impl Iterator for EraStakePairIter {
     }
 }
 
+/// (De)serialization of `Balance` values for off-chain tooling.
+///
+/// Values are serialized as decimal strings, since `u128` values can exceed the safe integer range of JSON.
+/// When deserializing, decimal strings of any `Balance` value are accepted, while plain numbers are only accepted up to `u64::MAX`.
+/// Larger plain numbers are rejected, since e.g. `serde_json` parses them as floats, losing precision.
+#[cfg(feature = "std")]
+mod serde_balance {
+    use super::Balance;
+    use serde::{de, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &Balance, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&value.to_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Balance, D::Error> {
+        struct BalanceVisitor;
+
+        impl<'de> de::Visitor<'de> for BalanceVisitor {
+            type Value = Balance;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str(
+                    "a balance, as a decimal string or a non-negative number up to u64::MAX",
+                )
+            }
+
+            fn visit_u64<E: de::Error>(self, value: u64) -> Result<Balance, E> {
+                Ok(value.into())
+            }
+
+            fn visit_u128<E: de::Error>(self, value: u128) -> Result<Balance, E> {
+                Ok(value)
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Balance, E> {
+                value.parse().map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_any(BalanceVisitor)
+    }
+}
+
 /// Describes stake amount in an particular era/period.
+///
+/// When `std` feature is enabled, it can be (de)serialized, with stake amounts encoded as decimal strings.
 #[derive(Encode, Decode, MaxEncodedLen, Copy, Clone, Debug, PartialEq, Eq, TypeInfo, Default)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 pub struct StakeAmount {
     /// Amount of staked funds accounting for the voting subperiod.
     #[codec(compact)]
+    #[cfg_attr(feature = "std", serde(with = "serde_balance"))]
     pub voting: Balance,
     /// Amount of staked funds accounting for the build&earn subperiod.
     #[codec(compact)]
+    #[cfg_attr(feature = "std", serde(with = "serde_balance"))]
     pub build_and_earn: Balance,
     /// Era to which this stake amount refers to.
     #[codec(compact)]
@@ -883,6 +1122,93 @@ impl StakeAmount {
             }
         }
     }
+
+    /// Era-count weighted average of the specified stake samples.
+    ///
+    /// Each sample is a pair of `(eras, stake)`, where `eras` is the number of eras for which the `stake` was held.
+    /// Both stake components are averaged independently, rounding down. Era & period are taken from the last sample.
+    ///
+    /// In case there are no samples, or all weights are zero, zero stake amount is returned.
+    pub fn weighted_average(samples: &[(EraNumber, StakeAmount)]) -> StakeAmount {
+        let total_weight = samples.iter().fold(Balance::zero(), |acc, (eras, _)| {
+            acc.saturating_add((*eras).into())
+        });
+        if total_weight.is_zero() {
+            return StakeAmount::default();
+        }
+
+        let (voting_sum, build_and_earn_sum) = samples.iter().fold(
+            (Balance::zero(), Balance::zero()),
+            |(voting, build_and_earn), (eras, stake)| {
+                let weight = Balance::from(*eras);
+                (
+                    voting.saturating_add(stake.voting.saturating_mul(weight)),
+                    build_and_earn.saturating_add(stake.build_and_earn.saturating_mul(weight)),
+                )
+            },
+        );
+
+        let (era, period) = samples
+            .last()
+            .map(|(_, stake)| (stake.era, stake.period))
+            .unwrap_or_default();
+
+        StakeAmount {
+            voting: voting_sum / total_weight,
+            build_and_earn: build_and_earn_sum / total_weight,
+            era,
+            period,
+        }
+    }
+
+    /// Stake amount carried over into the next period.
+    ///
+    /// Voting stake is kept as-is, while the build&earn stake is cleared.
+    /// Period is incremented, and era is left unchanged since it's up to the caller to decide
+    /// from which era of the next period the stake is valid.
+    pub fn roll_to_next_period(&self) -> StakeAmount {
+        StakeAmount {
+            voting: self.voting,
+            build_and_earn: Balance::zero(),
+            era: self.era,
+            period: self.period.saturating_add(1),
+        }
+    }
+
+    /// Stake amount with both components scaled by the `factor`, rounding down.
+    ///
+    /// Era & period are left unchanged.
+    pub fn scale(&self, factor: Perbill) -> StakeAmount {
+        StakeAmount {
+            voting: factor.mul_floor(self.voting),
+            build_and_earn: factor.mul_floor(self.build_and_earn),
+            era: self.era,
+            period: self.period,
+        }
+    }
+
+    /// Stake amount with both components multiplied by the integer `multiplier`, e.g. due to a denomination change.
+    ///
+    /// Components saturate at `Balance::MAX` instead of overflowing. Era & period are left unchanged.
+    pub fn rescale_denomination(&self, multiplier: u128) -> StakeAmount {
+        StakeAmount {
+            voting: self.voting.saturating_mul(multiplier),
+            build_and_earn: self.build_and_earn.saturating_mul(multiplier),
+            era: self.era,
+            period: self.period,
+        }
+    }
+}
+
+/// Compare two stake amounts by their total stake, for ranking purposes.
+///
+/// If the totals are equal, the stake amount with the larger voting component is considered greater,
+/// since voting stake is eligible for the bonus reward. Era & period aren't considered, so amounts
+/// with equal components are always `Ordering::Equal`, which keeps stable sorts reproducible.
+pub fn cmp_by_total(a: &StakeAmount, b: &StakeAmount) -> Ordering {
+    a.total()
+        .cmp(&b.total())
+        .then_with(|| a.voting.cmp(&b.voting))
 }
 
 /// Info about an era, including the rewards, how much is locked, unlocking, etc.
@@ -1073,6 +1399,64 @@ impl SingularStakingInfo {
     pub fn is_empty(&self) -> bool {
         self.staked.is_empty()
     }
+
+    /// Move the specified `amount` of voting stake from one contract to another, preserving bonus eligibility.
+    ///
+    /// Meant to be used during the `Voting` subperiod, when both entries must refer to the same period.
+    /// Fails if periods differ, or if `amount` exceeds the voting stake of `from`, in which case nothing is changed.
+    ///
+    /// Bonus eligibility is handled as follows:
+    /// * `from` remains eligible as long as some voting stake remains on it. Full transfer removes its eligibility.
+    /// * `to` remains eligible only if both `to` & `from` were eligible, i.e. eligibility cannot be gained by restaking.
+    pub fn restake_preserving_bonus(
+        from: &mut Self,
+        to: &mut Self,
+        amount: Balance,
+        current_era: EraNumber,
+    ) -> Result<(), ()> {
+        if from.period_number() != to.period_number() || amount > from.staked.voting {
+            return Err(());
+        }
+
+        let from_loyal = from.loyal_staker;
+        from.unstake(amount, current_era, Subperiod::Voting);
+        to.stake(amount, current_era, Subperiod::Voting);
+        to.loyal_staker = to.loyal_staker && from_loyal;
+
+        Ok(())
+    }
+
+    /// Derive the bonus status of a staker at the end of the voting subperiod, from its stake amount.
+    ///
+    /// Only the voting component of the stake is taken into account, while era & period are taken over as-is.
+    /// Staker is eligible for the bonus only if the stake has been `maintained_through_voting`.
+    pub fn derive_bonus_status(stake: &StakeAmount, maintained_through_voting: bool) -> Self {
+        Self {
+            staked: StakeAmount {
+                voting: stake.voting,
+                build_and_earn: Balance::zero(),
+                era: stake.era,
+                period: stake.period,
+            },
+            loyal_staker: maintained_through_voting,
+        }
+    }
+}
+
+/// Whether the staker described by `status` is eligible for the bonus reward.
+///
+/// Some voting stake is always required. If `requires_full` is `true` (see `CycleConfiguration::bonus_requires_full_voting`),
+/// the stake must also have been `maintained_full` throughout the voting subperiod, and the staker must still be loyal.
+pub fn bonus_eligible_from_status(
+    status: &SingularStakingInfo,
+    maintained_full: bool,
+    requires_full: bool,
+) -> bool {
+    if status.staked_amount(Subperiod::Voting).is_zero() {
+        return false;
+    }
+
+    !requires_full || (maintained_full && status.is_loyal())
 }
 
 /// Composite type that holds information about how much was staked on a contract in up to two distinct eras.
@@ -1462,6 +1846,59 @@ impl<NT: Get<u32>> TierParameters<NT> {
             && number_of_tiers == self.slot_distribution.len()
             && number_of_tiers == self.tier_thresholds.len()
     }
+
+    /// Split the dApp reward `pool` between the tiers, according to their reward portions.
+    ///
+    /// Each tier is allocated `reward_portion * pool`, rounded down.
+    /// The remainder, i.e. the rounding dust & any portion not covered by the reward portions,
+    /// is assigned to the top tier (tier `0`), so the allocations always sum up exactly to the `pool`.
+    /// In case there are no tiers, nothing is allocated.
+    pub fn dapp_pool_by_tier(&self, pool: Balance) -> Vec<(TierId, Balance)> {
+        let mut allocations: Vec<(TierId, Balance)> = self
+            .reward_portion
+            .iter()
+            .enumerate()
+            .map(|(idx, portion)| (idx.unique_saturated_into(), portion.mul_floor(pool)))
+            .collect();
+
+        let allocated = allocations
+            .iter()
+            .fold(Balance::zero(), |acc, (_, amount)| {
+                acc.saturating_add(*amount)
+            });
+        if let Some((_, top_tier_amount)) = allocations.first_mut() {
+            top_tier_amount.saturating_accrue(pool.saturating_sub(allocated));
+        }
+
+        allocations
+    }
+
+    /// Proportionally rescale the reward portions, so they sum up to exactly 100%.
+    ///
+    /// Each portion is rescaled with rounding down, and the remainder is assigned to the top tier (tier `0`).
+    /// In case there are no tiers, or all the portions are zero, nothing is changed since the proportions are undefined.
+    pub fn normalize_tier_rewards(&mut self) {
+        let accuracy = u64::from(Permill::one().deconstruct());
+        let total = self.reward_portion.iter().fold(0_u64, |acc, portion| {
+            acc.saturating_add(portion.deconstruct().into())
+        });
+        if total.is_zero() {
+            return;
+        }
+
+        let mut normalized_total = 0_u64;
+        for portion in self.reward_portion.iter_mut() {
+            let parts = u64::from(portion.deconstruct()).saturating_mul(accuracy) / total;
+            normalized_total.saturating_accrue(parts);
+            *portion = Permill::from_parts(parts.unique_saturated_into());
+        }
+
+        if let Some(top_tier_portion) = self.reward_portion.get_mut(0) {
+            let remainder = accuracy.saturating_sub(normalized_total);
+            *top_tier_portion = top_tier_portion
+                .saturating_add(Permill::from_parts(remainder.unique_saturated_into()));
+        }
+    }
 }
 
 impl<NT: Get<u32>> Default for TierParameters<NT> {
@@ -1624,6 +2061,54 @@ impl<NT: Get<u32>> TiersConfiguration<NT> {
         }
     }
 
+    /// Scale number of slots of each tier by the specified `factor`, rounding down.
+    ///
+    /// Tier which had at least one slot will never be scaled below one slot, while tiers without any slots remain empty.
+    /// Total number of slots is updated accordingly, all other values are left unchanged.
+    pub fn scale_slots(&self, factor: FixedU64) -> Self {
+        let mut slots_per_tier = self.slots_per_tier.clone();
+        slots_per_tier.iter_mut().for_each(|slots| {
+            if *slots > 0 {
+                *slots = factor.saturating_mul_int(*slots).max(1);
+            }
+        });
+
+        Self {
+            number_of_slots: slots_per_tier
+                .iter()
+                .fold(0, |acc: u16, x| acc.saturating_add(*x)),
+            slots_per_tier,
+            reward_portion: self.reward_portion.clone(),
+            tier_thresholds: self.tier_thresholds.clone(),
+        }
+    }
+
+    /// Utilization of each tier, i.e. the ratio of occupied slots to the tier slot capacity.
+    ///
+    /// `counts` contains the number of occupied slots per tier, tiers without an entry are considered empty.
+    /// Utilization is capped at 100%, in case tier is over-assigned, and is zero for tiers without any slots.
+    pub fn tier_utilization(&self, counts: &[(TierId, u16)]) -> Vec<(TierId, Perbill)> {
+        self.slots_per_tier
+            .iter()
+            .enumerate()
+            .map(|(idx, capacity)| {
+                let tier_id: TierId = idx.unique_saturated_into();
+                let occupied = counts
+                    .iter()
+                    .filter(|(id, _)| *id == tier_id)
+                    .fold(0_u16, |acc, (_, count)| acc.saturating_add(*count));
+
+                let utilization = if capacity.is_zero() {
+                    Perbill::zero()
+                } else {
+                    Perbill::from_rational(occupied.min(*capacity), *capacity)
+                };
+
+                (tier_id, utilization)
+            })
+            .collect()
+    }
+
     /// Calculate number of slots, based on the provided native token price.
     pub fn calculate_number_of_slots(native_price: FixedU64) -> u16 {
         // floor(1000 x price + 50), formula proposed in Tokenomics 2.0 document.
@@ -100,7 +100,8 @@ pub use pallet::*;
 
 use astar_primitives::{
     dapp_staking::{
-        CycleConfiguration, EraNumber, Observer as DappStakingObserver, StakingRewardHandler,
+        snapshot_from_pools, CycleConfiguration, CycleGeometry, EraNumber, InflationSnapshot,
+        Observer as DappStakingObserver, RewardPayoutError, RewardPools, StakingRewardHandler,
     },
     Balance,
 };
@@ -157,6 +158,9 @@ pub mod pallet {
         /// Cycle ('year') configuration - covers periods, subperiods, eras & blocks.
         type CycleConfiguration: CycleConfiguration;
 
+        /// Observers notified once the inflation configuration has been recalculated.
+        type Observers: DappStakingObserver;
+
         /// The overarching event type.
         type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
 
@@ -243,6 +247,12 @@ pub mod pallet {
                 ActiveInflationConfig::<T>::put(config.clone());
                 DoRecalculation::<T>::kill();
 
+                let observers_weight = Self::notify_inflation_recalculated(next_era, &config);
+                frame_system::Pallet::<T>::register_extra_weight_unchecked(
+                    observers_weight,
+                    DispatchClass::Mandatory,
+                );
+
                 Self::deposit_event(Event::<T>::NewInflationConfiguration { config });
             }
 
@@ -287,21 +297,27 @@ pub mod pallet {
         /// Must be called by `root` origin.
         ///
         /// Purpose of the call is testing & handling unforeseen circumstances.
+        /// Actual weight of the call includes the weight consumed by the observers.
         #[pallet::call_index(1)]
         #[pallet::weight(T::WeightInfo::force_inflation_recalculation())]
         pub fn force_inflation_recalculation(
             origin: OriginFor<T>,
             next_era: EraNumber,
-        ) -> DispatchResult {
+        ) -> DispatchResultWithPostInfo {
             ensure_root(origin)?;
 
             let config = Self::recalculate_inflation(next_era);
 
             ActiveInflationConfig::<T>::put(config.clone());
 
+            let observers_weight = Self::notify_inflation_recalculated(next_era, &config);
+
             Self::deposit_event(Event::<T>::ForcedInflationRecalculation { config });
 
-            Ok(().into())
+            Ok(Some(
+                T::WeightInfo::force_inflation_recalculation().saturating_add(observers_weight),
+            )
+            .into())
         }
     }
 
@@ -389,6 +405,20 @@ pub mod pallet {
             new_inflation_config
         }
 
+        /// Notify the observers about the recalculated inflation `config`, which applies from the `next_era`.
+        ///
+        /// Returns the weight consumed by the observers.
+        pub(crate) fn notify_inflation_recalculated(
+            next_era: EraNumber,
+            config: &InflationConfiguration,
+        ) -> Weight {
+            let cycle = T::CycleConfiguration::cycle_number_for_era(next_era);
+            T::Observers::on_inflation_recalculated(
+                cycle,
+                &config.snapshot::<T::CycleConfiguration>(),
+            )
+        }
+
         /// Check if payout cap limit would be reached after payout.
         fn is_payout_cap_limit_exceeded(payout: Balance) -> bool {
             let config = ActiveInflationConfig::<T>::get();
@@ -449,9 +479,12 @@ pub mod pallet {
             ActiveInflationConfig::<T>::get().bonus_reward_pool_per_period
         }
 
-        fn payout_reward(account: &T::AccountId, reward: Balance) -> Result<(), ()> {
+        fn payout_reward(account: &T::AccountId, reward: Balance) -> Result<(), RewardPayoutError> {
             // This is a safety measure to prevent excessive minting.
-            ensure!(!Self::is_payout_cap_limit_exceeded(reward), ());
+            ensure!(
+                !Self::is_payout_cap_limit_exceeded(reward),
+                RewardPayoutError::Failed
+            );
 
             // This can fail only if the amount is below existential deposit & the account doesn't exist,
             // or if the account has no provider references.
@@ -501,6 +534,25 @@ pub struct InflationConfiguration {
 }
 
 impl InflationConfiguration {
+    /// Snapshot of the reward pools described by this configuration, for the cycle configuration `C`.
+    ///
+    /// Cycle totals are scaled as described by [`snapshot_from_pools`], assuming the adjustable staker pool is paid out in full.
+    pub fn snapshot<C: CycleConfiguration>(&self) -> InflationSnapshot {
+        let pools = RewardPools {
+            staker: self
+                .base_staker_reward_pool_per_era
+                .saturating_add(self.adjustable_staker_reward_pool_per_era),
+            dapp: self.dapp_reward_pool_per_era,
+            bonus: self.bonus_reward_pool_per_period,
+        };
+
+        InflationSnapshot {
+            base_staker_reward_pool_per_era: self.base_staker_reward_pool_per_era,
+            adjustable_staker_reward_pool_per_era: self.adjustable_staker_reward_pool_per_era,
+            ..snapshot_from_pools(&pools, &CycleGeometry::from_config::<C>())
+        }
+    }
+
     /// Sanity check that does rudimentary checks on the configuration and prints warnings if something is unexpected.
     ///
     /// There are no strict checks, since the configuration values aren't strictly bounded like those of the parameters.
@@ -641,6 +693,7 @@ impl<T: Config, P: Get<(InflationParameters, EraNumber, Weight)>> OnRuntimeUpgra
         // 2. Calculation inflation config, set it & deposit event
         let config = Pallet::<T>::recalculate_inflation(next_era);
         ActiveInflationConfig::<T>::put(config.clone());
+        let observers_weight = Pallet::<T>::notify_inflation_recalculated(next_era, &config);
 
         Pallet::<T>::deposit_event(Event::<T>::NewInflationConfiguration { config });
 
@@ -651,6 +704,7 @@ impl<T: Config, P: Get<(InflationParameters, EraNumber, Weight)>> OnRuntimeUpgra
 
         T::WeightInfo::recalculation()
             .saturating_add(T::DbWeight::get().reads_writes(1, 2))
+            .saturating_add(observers_weight)
             .saturating_add(extra_weight)
     }
 
@@ -35,7 +35,11 @@ use sp_runtime::{
     BuildStorage, Perquintill,
 };
 
-use astar_primitives::{Balance, BlockNumber};
+use astar_primitives::{
+    dapp_staking::{InflationSnapshot, Observer as DappStakingObserver},
+    Balance, BlockNumber,
+};
+use sp_std::cell::RefCell;
 pub(crate) type AccountId = u64;
 
 /// Initial inflation params set by the mock.
@@ -133,10 +137,23 @@ impl CycleConfiguration for DummyCycleConfiguration {
     }
 }
 
+thread_local! {
+    pub(crate) static INFLATION_RECALCULATED: RefCell<Option<(u32, InflationSnapshot)>> = RefCell::new(None);
+}
+
+pub struct DummyObserver;
+impl DappStakingObserver for DummyObserver {
+    fn on_inflation_recalculated(cycle: u32, new_params: &InflationSnapshot) -> Weight {
+        INFLATION_RECALCULATED.with(|v| *v.borrow_mut() = Some((cycle, *new_params)));
+        Weight::from_parts(3, 4)
+    }
+}
+
 impl pallet_inflation::Config for Test {
     type Currency = Balances;
     type PayoutPerBlock = DummyPayoutPerBlock;
     type CycleConfiguration = DummyCycleConfiguration;
+    type Observers = DummyObserver;
     type RuntimeEvent = RuntimeEvent;
     type WeightInfo = ();
 }
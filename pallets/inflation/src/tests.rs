@@ -17,6 +17,7 @@
 // along with Astar. If not, see <http://www.gnu.org/licenses/>.
 
 use super::{pallet::Error, Event, *};
+use astar_primitives::dapp_staking::RewardPayoutError;
 use frame_support::{assert_noop, assert_ok, assert_storage_noop, traits::Hooks};
 use mock::*;
 use sp_runtime::{
@@ -78,10 +79,17 @@ fn force_inflation_recalculation_work() {
 
         // Execute call, ensure it works
         let next_era = 100;
-        assert_ok!(Inflation::force_inflation_recalculation(
-            RuntimeOrigin::root(),
-            next_era,
-        ));
+        let post_info =
+            Inflation::force_inflation_recalculation(RuntimeOrigin::root(), next_era).unwrap();
+
+        // Observers' weight is included in the actual weight
+        assert_eq!(
+            post_info.actual_weight,
+            Some(
+                <Test as Config>::WeightInfo::force_inflation_recalculation()
+                    .saturating_add(Weight::from_parts(3, 4))
+            )
+        );
 
         let new_config = ActiveInflationConfig::<Test>::get();
         assert_ne!(
@@ -92,6 +100,16 @@ fn force_inflation_recalculation_work() {
         System::assert_last_event(
             Event::ForcedInflationRecalculation { config: new_config }.into(),
         );
+
+        // Observers are notified about the new config
+        let cycle = <Test as Config>::CycleConfiguration::cycle_number_for_era(next_era);
+        assert_eq!(
+            INFLATION_RECALCULATED.with(|v| *v.borrow()),
+            Some((
+                cycle,
+                new_config.snapshot::<<Test as Config>::CycleConfiguration>()
+            ))
+        );
     })
 }
 #[test]
@@ -124,8 +142,10 @@ fn inflation_recalculation_occurs_when_expected() {
         Inflation::block_before_new_era(recalculation_era);
         let init_config = ActiveInflationConfig::<Test>::get();
         let init_total_issuance = Balances::total_issuance();
+        assert!(INFLATION_RECALCULATED.with(|v| v.borrow().is_none()));
 
         // Finally trigger inflation recalculation.
+        let init_block_weight = System::block_weight().total();
         Inflation::on_finalize(300);
 
         let new_config = ActiveInflationConfig::<Test>::get();
@@ -135,6 +155,22 @@ fn inflation_recalculation_occurs_when_expected() {
         );
         System::assert_last_event(Event::NewInflationConfiguration { config: new_config }.into());
 
+        // Observers are notified about the new config, in the cycle starting with the recalculation era
+        let cycle = <Test as Config>::CycleConfiguration::cycle_number_for_era(recalculation_era);
+        assert_eq!(cycle, 2);
+        assert_eq!(
+            INFLATION_RECALCULATED.with(|v| *v.borrow()),
+            Some((
+                cycle,
+                new_config.snapshot::<<Test as Config>::CycleConfiguration>()
+            ))
+        );
+        assert_eq!(
+            System::block_weight().total(),
+            init_block_weight.saturating_add(Weight::from_parts(3, 4)),
+            "Observer weight must be accounted for."
+        );
+
         assert_eq!(
             Balances::total_issuance(),
             init_total_issuance,
@@ -415,7 +451,10 @@ fn payout_reward_fails_when_relaxed_cap_is_exceeded() {
         let reward = relaxed_cap - Balances::total_issuance() + 1;
 
         // Payout should be a failure, with storage noop.
-        assert_noop!(Inflation::payout_reward(&account, reward), ());
+        assert_noop!(
+            Inflation::payout_reward(&account, reward),
+            RewardPayoutError::Failed
+        );
     })
 }
 
@@ -449,6 +488,32 @@ fn cycle_configuration_works() {
     })
 }
 
+#[test]
+fn inflation_configuration_snapshot_works() {
+    let config = InflationConfiguration {
+        dapp_reward_pool_per_era: 10,
+        base_staker_reward_pool_per_era: 20,
+        adjustable_staker_reward_pool_per_era: 30,
+        bonus_reward_pool_per_period: 40,
+        ..Default::default()
+    };
+
+    // 5 periods per cycle, each with 17 build&earn eras
+    let snapshot = config.snapshot::<DummyCycleConfiguration>();
+    assert_eq!(snapshot.dapp_reward_pool_per_era, 10);
+    assert_eq!(snapshot.base_staker_reward_pool_per_era, 20);
+    assert_eq!(snapshot.adjustable_staker_reward_pool_per_era, 30);
+    assert_eq!(snapshot.bonus_reward_pool_per_period, 40);
+    assert_eq!(
+        snapshot.per_cycle,
+        RewardPools {
+            staker: 50 * 85,
+            dapp: 10 * 85,
+            bonus: 40 * 5,
+        }
+    );
+}
+
 #[test]
 fn test_genesis_build() {
     ExternalityBuilder::build().execute_with(|| {
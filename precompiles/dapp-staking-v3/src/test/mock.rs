@@ -42,7 +42,8 @@ extern crate alloc;
 
 use astar_primitives::{
     dapp_staking::{
-        CycleConfiguration, EraNumber, PeriodNumber, SmartContract, StakingRewardHandler,
+        CycleConfiguration, EraNumber, PeriodNumber, RewardPayoutError, SmartContract,
+        StakingRewardHandler,
     },
     oracle::PriceProvider,
     AccountId, Balance, BlockNumber,
@@ -203,7 +204,7 @@ impl StakingRewardHandler<AccountId> for DummyStakingRewardHandler {
         Balance::from(3_000_000_u128)
     }
 
-    fn payout_reward(beneficiary: &AccountId, reward: Balance) -> Result<(), ()> {
+    fn payout_reward(beneficiary: &AccountId, reward: Balance) -> Result<(), RewardPayoutError> {
         let _ = Balances::mint_into(beneficiary, reward);
         Ok(())
     }
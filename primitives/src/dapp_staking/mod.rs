@@ -0,0 +1,2571 @@
+// This file is part of Astar.
+
+// Copyright (C) 2019-2023 Stake Technologies Pte.Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// Astar is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Astar is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Astar. If not, see <http://www.gnu.org/licenses/>.
+
+use super::{Balance, BlockNumber};
+
+use parity_scale_codec::{Decode, Encode, MaxEncodedLen};
+
+use frame_support::{
+    pallet_prelude::{RuntimeDebug, Weight},
+    traits::{ConstU32, Get},
+    BoundedBTreeSet, BoundedVec, CloneNoBound, EqNoBound, PartialEqNoBound, RuntimeDebugNoBound,
+};
+use sp_arithmetic::{helpers_128bit::multiply_by_rational_with_rounding, Rounding};
+use sp_core::H160;
+use sp_runtime::{
+    traits::{Saturating, Zero},
+    Perbill, Perquintill,
+};
+use sp_std::{
+    collections::btree_map::{BTreeMap, Entry},
+    fmt,
+    hash::Hash,
+    marker::PhantomData,
+    vec::Vec,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Era number type
+pub type EraNumber = u32;
+/// Period number type
+pub type PeriodNumber = u32;
+/// Dapp Id type
+pub type DAppId = u16;
+/// Tier Id type
+pub type TierId = u8;
+/// Assignment of dApps into tiers for an era
+pub type DAppTierAssignment = BTreeMap<DAppId, TierId>;
+
+/// Distinct subperiods in dApp staking protocol.
+#[derive(
+    Encode, Decode, MaxEncodedLen, Clone, Copy, RuntimeDebug, PartialEq, Eq, scale_info::TypeInfo,
+)]
+pub enum Subperiod {
+    /// Subperiod during which the focus is on voting. No rewards are earned during this subperiod.
+    Voting,
+    /// Subperiod during which dApps and stakers earn rewards.
+    BuildAndEarn,
+}
+
+impl Subperiod {
+    /// Next subperiod, after `self`.
+    pub fn next(&self) -> Self {
+        match self {
+            Subperiod::Voting => Subperiod::BuildAndEarn,
+            Subperiod::BuildAndEarn => Subperiod::Voting,
+        }
+    }
+}
+
+/// Configuration for cycles, periods, subperiods & eras.
+///
+/// * `cycle` - Time unit similar to 'year' in the real world. Consists of one or more periods. At the beginning of each cycle, inflation is recalculated.
+/// * `period` - Period consists of two distinct subperiods: `Voting` & `Build&Earn`. They are integral parts of dApp staking.
+///              Length is expressed in standard eras or just _eras_.
+/// * `era` - Era is the basic time unit in the dApp staking protocol. At the end of each era, reward pools for stakers & dApps are calculated.
+///           Era length is expressed in blocks.
+///
+/// Helpers which map an era number onto the protocol timeline assume the nominal schedule:
+/// the first era is [`CycleConfiguration::FIRST_ERA`], and each period consists of exactly one voting subperiod era, followed by
+/// `eras_per_build_and_earn_subperiod` build&earn eras. Forced era or subperiod changes aren't accounted for.
+pub trait CycleConfiguration {
+    /// Minimum number of blocks a cycle must last, checked as part of [`Self::validate`].
+    ///
+    /// Inflation parameters are recalculated once per cycle, with the assumption that a cycle spans a substantial
+    /// amount of time. Extremely short cycles would cause overly frequent recalculations, so implementations can
+    /// set a lower bound here. By default, there's no lower bound.
+    const MIN_CYCLE_BLOCKS: BlockNumber = 0;
+
+    /// Number of the first era, i.e. the era indexing base used by the era helpers.
+    ///
+    /// Eras are numbered from `1` by default. Implementations can override it to `0` if needed,
+    /// in which case no era precedes the first era.
+    const FIRST_ERA: EraNumber = 1;
+
+    /// How many different periods are there in a cycle (a 'year').
+    ///
+    /// This value has to be at least 1.
+    fn periods_per_cycle() -> PeriodNumber;
+
+    /// For how many standard era lengths does the voting subperiod last.
+    ///
+    /// This value has to be at least 1.
+    fn eras_per_voting_subperiod() -> EraNumber;
+
+    /// How many standard eras are there in the build&earn subperiod.
+    ///
+    /// This value has to be at least 1.
+    fn eras_per_build_and_earn_subperiod() -> EraNumber;
+
+    /// How many blocks are there per standard era.
+    ///
+    /// This value has to be at least 1.
+    fn blocks_per_era() -> BlockNumber;
+
+    /// For how many standard era lengths does the period last.
+    fn period_in_era_lengths() -> EraNumber {
+        Self::eras_per_voting_subperiod().saturating_add(Self::eras_per_build_and_earn_subperiod())
+    }
+
+    /// For how many standard era lengths does the cycle (a 'year') last.
+    fn cycle_in_era_lengths() -> EraNumber {
+        Self::period_in_era_lengths().saturating_mul(Self::periods_per_cycle())
+    }
+
+    /// How many blocks are there per cycle (a 'year').
+    fn blocks_per_cycle() -> BlockNumber {
+        Self::blocks_per_era().saturating_mul(Self::cycle_in_era_lengths())
+    }
+
+    /// How many blocks are there per cycle (a 'year'), capped at `max`.
+    ///
+    /// Callers should supply the chain's effective block number ceiling as `max`,
+    /// to avoid nonsensical timelines for configurations where the cycle is longer than that.
+    fn blocks_per_cycle_capped(max: BlockNumber) -> BlockNumber {
+        Self::blocks_per_cycle().min(max)
+    }
+
+    /// For how many standard era lengths do all the build&earn subperiods in a cycle last.
+    fn build_and_earn_eras_per_cycle() -> EraNumber {
+        Self::eras_per_build_and_earn_subperiod().saturating_mul(Self::periods_per_cycle())
+    }
+
+    /// How many distinct eras are there in a single period.
+    fn eras_per_period() -> EraNumber {
+        Self::eras_per_build_and_earn_subperiod().saturating_add(1)
+    }
+
+    /// How many distinct eras are there in a cycle.
+    fn eras_per_cycle() -> EraNumber {
+        Self::eras_per_period().saturating_mul(Self::periods_per_cycle())
+    }
+
+    /// How many reward calculation events happen per cycle, i.e. one per era.
+    fn payout_events_per_cycle() -> u32 {
+        Self::eras_per_cycle()
+    }
+
+    /// How many blocks there are between two consecutive reward calculation events, i.e. standard era length.
+    fn blocks_between_payouts() -> BlockNumber {
+        Self::blocks_per_era()
+    }
+
+    /// Human readable summary of the cycle configuration durations, for the specified block time in seconds.
+    ///
+    /// All the durations are rounded down to the whole unit, e.g. an era of 90 seconds is reported as 1 minute.
+    fn humanize(block_time_secs: u32) -> CycleHumanSummary {
+        const SECS_PER_MINUTE: u64 = 60;
+        const SECS_PER_DAY: u64 = 24 * 60 * 60;
+
+        let block_time_secs = u64::from(block_time_secs);
+        let era_secs = u64::from(Self::blocks_per_era()).saturating_mul(block_time_secs);
+        let period_secs = era_secs.saturating_mul(Self::period_in_era_lengths().into());
+        let cycle_secs = period_secs.saturating_mul(Self::periods_per_cycle().into());
+
+        CycleHumanSummary {
+            era_minutes: era_secs / SECS_PER_MINUTE,
+            period_days: period_secs / SECS_PER_DAY,
+            cycle_days: cycle_secs / SECS_PER_DAY,
+        }
+    }
+
+    /// `true` if a period consists of at least two distinct eras, `false` otherwise.
+    ///
+    /// Each period must have one voting subperiod era, followed by at least one build&earn era.
+    fn eras_per_period_is_valid() -> bool {
+        Self::eras_per_period() >= 2
+    }
+
+    /// `true` if the configuration is valid, `false` otherwise.
+    ///
+    /// Periods per cycle, voting subperiod length & era length must be at least 1. The build&earn subperiod
+    /// lower bound is enforced via the period length instead, which must satisfy [`Self::eras_per_period_is_valid`].
+    /// Cycle must also last for at least [`Self::MIN_CYCLE_BLOCKS`] blocks.
+    fn validate() -> bool {
+        Self::periods_per_cycle() > 0
+            && Self::eras_per_voting_subperiod() > 0
+            && Self::blocks_per_era() > 0
+            && Self::eras_per_period_is_valid()
+            && Self::cycle_length_at_least(Self::MIN_CYCLE_BLOCKS)
+    }
+
+    /// `true` if the cycle lasts for at least `min_blocks` blocks, `false` otherwise.
+    fn cycle_length_at_least(min_blocks: BlockNumber) -> bool {
+        Self::blocks_per_cycle() >= min_blocks
+    }
+
+    /// Number of eras in the `[from, to)` range, i.e. `from` is included while `to` is excluded.
+    ///
+    /// Returns `None` if `to` precedes `from`, and `Some(0)` for an empty range, when `from == to`.
+    fn eras_in_range(from: EraNumber, to: EraNumber) -> Option<EraNumber> {
+        to.checked_sub(from)
+    }
+
+    /// Check whether the transition from period `from` to period `to` is valid.
+    ///
+    /// Normally, the new period must be exactly one greater than the old one.
+    /// If the transition was `forced`, any period greater than the old one is accepted.
+    fn validate_period_transition(
+        from: PeriodNumber,
+        to: PeriodNumber,
+        forced: bool,
+    ) -> Result<(), ()> {
+        let is_valid = if forced {
+            to > from
+        } else {
+            from.checked_add(1) == Some(to)
+        };
+
+        if is_valid {
+            Ok(())
+        } else {
+            Err(())
+        }
+    }
+
+    /// Index of the standard era length, inside the period starting at `period_start_block`, to which `block` belongs.
+    ///
+    /// Index is counted in standard era lengths, so the (longer) voting subperiod spans multiple indices.
+    /// Blocks before the period start return index `0`.
+    fn era_in_period_for_block(block: BlockNumber, period_start_block: BlockNumber) -> EraNumber {
+        block.saturating_sub(period_start_block) / Self::blocks_per_era().max(1)
+    }
+
+    /// Number of blocks remaining in the standard era which started at `era_start`, after the `now` block.
+    ///
+    /// The `now` block isn't counted, so `0` is returned for the last block of the era.
+    fn remaining_blocks_in_era(now: BlockNumber, era_start: BlockNumber) -> BlockNumber {
+        Self::blocks_until_next_era(now, era_start).saturating_sub(1)
+    }
+
+    /// Number of blocks until the next standard era begins, for an era which started at `era_start`.
+    ///
+    /// The `now` block is counted, so the full era length is returned for `now == era_start`, and `1` for the last block of the era.
+    /// Unlike [`Self::remaining_blocks_in_era`], which returns `0` at the last block of the era, this never returns `0` while `now` is inside the era.
+    fn blocks_until_next_era(now: BlockNumber, era_start: BlockNumber) -> BlockNumber {
+        era_start
+            .saturating_add(Self::blocks_per_era())
+            .saturating_sub(now)
+    }
+
+    /// Number of blocks spanned by the specified number of standard eras.
+    ///
+    /// Saturates at the maximum block number.
+    fn eras_to_blocks(eras: EraNumber) -> BlockNumber {
+        eras.saturating_mul(Self::blocks_per_era())
+    }
+
+    /// Number of full standard eras spanned by the specified number of blocks.
+    ///
+    /// The result is rounded down, i.e. blocks of a partial era aren't counted.
+    fn blocks_to_eras(blocks: BlockNumber) -> EraNumber {
+        blocks / Self::blocks_per_era().max(1)
+    }
+
+    /// Start of an era which was delayed by a pause, e.g. while the chain was in maintenance or safe mode.
+    ///
+    /// `pause_blocks` is the total paused duration, not the duration of the latest pause.
+    /// Saturates at the maximum block number.
+    fn adjusted_era_start(original_start: BlockNumber, pause_blocks: BlockNumber) -> BlockNumber {
+        original_start.saturating_add(pause_blocks)
+    }
+
+    /// Length of the specified era, in standard era lengths.
+    ///
+    /// Voting subperiod era lasts for [`Self::eras_per_voting_subperiod`] standard era lengths, build&earn eras for exactly one.
+    fn era_in_era_lengths(era: EraNumber) -> EraNumber {
+        match Self::subperiod_for_era(era) {
+            Subperiod::Voting => Self::eras_per_voting_subperiod(),
+            Subperiod::BuildAndEarn => 1,
+        }
+    }
+
+    /// Number of standard era lengths elapsed from the start of `from_era` until the start of `to_era`.
+    ///
+    /// Zero is returned if `to_era` doesn't come after `from_era`.
+    fn era_lengths_between(from_era: EraNumber, to_era: EraNumber) -> u64 {
+        let elapsed_eras = to_era.saturating_sub(from_era);
+        let eras_per_period = Self::eras_per_period().max(1);
+
+        // Full periods have a fixed length, only the remaining eras need to be checked one by one.
+        let full_periods = u64::from(elapsed_eras / eras_per_period);
+        let remaining_eras_start = to_era.saturating_sub(elapsed_eras % eras_per_period);
+        let remaining_lengths = (remaining_eras_start..to_era).fold(0_u64, |acc, era| {
+            acc.saturating_add(Self::era_in_era_lengths(era).into())
+        });
+
+        full_periods
+            .saturating_mul(Self::period_in_era_lengths().into())
+            .saturating_add(remaining_lengths)
+    }
+
+    /// Approximate timestamp of the specified era start, in milliseconds.
+    ///
+    /// `genesis_era` is assumed to start at `genesis_ts_ms`, and all the blocks are assumed to take exactly `block_time_ms`,
+    /// so the result is only an approximation. Voting subperiod eras are accounted for with their full length.
+    /// Eras preceding the `genesis_era` return `genesis_ts_ms`.
+    fn era_start_timestamp(
+        era: EraNumber,
+        genesis_ts_ms: u64,
+        block_time_ms: u64,
+        genesis_era: EraNumber,
+    ) -> u64 {
+        genesis_ts_ms.saturating_add(
+            Self::era_lengths_between(genesis_era, era)
+                .saturating_mul(Self::blocks_per_era().into())
+                .saturating_mul(block_time_ms),
+        )
+    }
+
+    /// First block of the specified era, assuming the first era starts at `genesis_block`.
+    ///
+    /// `None` is returned if the block number would overflow. Eras preceding the first era return `genesis_block`.
+    fn first_block_of_era_from(era: EraNumber, genesis_block: BlockNumber) -> Option<BlockNumber> {
+        let elapsed_blocks = Self::era_lengths_between(Self::FIRST_ERA, era)
+            .checked_mul(Self::blocks_per_era().into())?;
+
+        BlockNumber::try_from(elapsed_blocks)
+            .ok()
+            .and_then(|elapsed_blocks| genesis_block.checked_add(elapsed_blocks))
+    }
+
+    /// Table of `(era, first_block)` pairs for `count` consecutive eras, starting with `from_era`.
+    ///
+    /// Eager counterpart of [`Self::first_block_of_era_from`], where each entry is derived from the previous one.
+    /// Table ends early if the block number would overflow.
+    fn era_block_table(
+        from_era: EraNumber,
+        count: u32,
+        genesis_block: BlockNumber,
+    ) -> Vec<(EraNumber, BlockNumber)> {
+        let mut table = Vec::new();
+        let mut next_first_block = Self::first_block_of_era_from(from_era, genesis_block);
+
+        for era in from_era..from_era.saturating_add(count) {
+            let first_block = match next_first_block {
+                Some(first_block) => first_block,
+                None => break,
+            };
+            table.push((era, first_block));
+
+            next_first_block = Self::era_in_era_lengths(era)
+                .checked_mul(Self::blocks_per_era())
+                .and_then(|era_length| first_block.checked_add(era_length));
+        }
+
+        table
+    }
+
+    /// Index of the specified era inside its period.
+    ///
+    /// Index `0` is the voting subperiod era, all the other indices belong to the build&earn subperiod.
+    fn era_index_in_period(era: EraNumber) -> EraNumber {
+        era.saturating_sub(Self::FIRST_ERA) % Self::eras_per_period().max(1)
+    }
+
+    /// `true` if the specified era is the last era of its period, i.e. the last build&earn era.
+    ///
+    /// Exactly one era per period satisfies this.
+    fn is_last_era_of_period(era: EraNumber) -> bool {
+        Self::era_index_in_period(era) == Self::eras_per_period().saturating_sub(1)
+    }
+
+    /// `true` if the specified era is the last era of its cycle, i.e. the last era of the cycle's last period.
+    ///
+    /// Exactly one era per cycle satisfies this.
+    fn is_last_era_of_cycle(era: EraNumber) -> bool {
+        let eras_per_cycle = Self::eras_per_cycle().max(1);
+        era.saturating_sub(Self::FIRST_ERA) % eras_per_cycle == eras_per_cycle - 1
+    }
+
+    /// Number of the period to which the specified era belongs.
+    ///
+    /// Periods are numbered from `1`, so the first era belongs to the first period.
+    /// Eras preceding the [`Self::FIRST_ERA`] belong to period `0`.
+    fn period_number_for_era(era: EraNumber) -> PeriodNumber {
+        if era < Self::FIRST_ERA {
+            0
+        } else {
+            (era - Self::FIRST_ERA) / Self::eras_per_period().max(1) + 1
+        }
+    }
+
+    /// Report of the period transition which happens when the specified `era` starts at `block`.
+    ///
+    /// `era` is expected to be the first era of the new period, i.e. its voting subperiod era.
+    /// The report describes the transition from the period preceding the one to which `era` belongs.
+    fn period_transition_at(era: EraNumber, block: BlockNumber) -> PeriodTransition {
+        let to = Self::period_number_for_era(era);
+
+        PeriodTransition {
+            from: to.saturating_sub(1),
+            to,
+            at_era: era,
+            at_block: block,
+            new_subperiod: Self::subperiod_for_era(era),
+        }
+    }
+
+    /// `true` if the specified era belongs to the specified cycle, `false` otherwise.
+    ///
+    /// Useful for bounds-checking eras received as user input.
+    fn is_era_in_cycle(era: EraNumber, cycle: u32) -> bool {
+        Self::cycle_number_for_era(era) == cycle
+    }
+
+    /// Portion of the cycle, to which `era` belongs, which has elapsed at `block`.
+    ///
+    /// `era` is expected to have started at `era_start` block, so any delays of the previous eras are accounted for.
+    /// The cycle start is derived from it, but it can never precede the `genesis_block`.
+    /// The result is rounded down, so the last block of the cycle is still reported below the full cycle,
+    /// while the first block of the next cycle's first era is reported as zero.
+    /// Eras preceding the [`Self::FIRST_ERA`] are reported as zero, and blocks past the cycle end are clamped to the full cycle.
+    fn percentage_of_cycle_elapsed(
+        era: EraNumber,
+        block: BlockNumber,
+        era_start: BlockNumber,
+        genesis_block: BlockNumber,
+    ) -> Perbill {
+        let blocks_per_cycle = Self::blocks_per_cycle();
+        if era < Self::FIRST_ERA || blocks_per_cycle.is_zero() {
+            return Perbill::zero();
+        }
+
+        let cycle_first_era = era - (era - Self::FIRST_ERA) % Self::eras_per_cycle().max(1);
+        let blocks_before_era = Self::era_lengths_between(cycle_first_era, era)
+            .saturating_mul(Self::blocks_per_era().into());
+        let blocks_before_era =
+            BlockNumber::try_from(blocks_before_era).unwrap_or(BlockNumber::MAX);
+
+        let cycle_start = era_start
+            .saturating_sub(blocks_before_era)
+            .max(genesis_block);
+        let elapsed_blocks = block.saturating_sub(cycle_start).min(blocks_per_cycle);
+
+        Perbill::from_rational(elapsed_blocks, blocks_per_cycle)
+    }
+
+    /// Kind of the boundary at the end of the specified era, i.e. which settlements must be done once it ends.
+    ///
+    /// Eras preceding the [`Self::FIRST_ERA`] don't mark any boundary.
+    fn boundary_kind(era: EraNumber) -> BoundaryKind {
+        if era < Self::FIRST_ERA {
+            BoundaryKind::None
+        } else if Self::is_last_era_of_cycle(era) {
+            BoundaryKind::EraAndCycle
+        } else if Self::is_last_era_of_period(era) {
+            BoundaryKind::EraAndPeriod
+        } else {
+            BoundaryKind::EraOnly
+        }
+    }
+
+    /// Whether bonus eligibility requires the stake to be maintained for the whole voting subperiod.
+    ///
+    /// If `false`, maintaining the stake for a part of the voting subperiod is sufficient.
+    fn bonus_requires_full_voting() -> bool {
+        true
+    }
+
+    /// Subperiod to which the specified era belongs.
+    fn subperiod_for_era(era: EraNumber) -> Subperiod {
+        if Self::era_index_in_period(era).is_zero() {
+            Subperiod::Voting
+        } else {
+            Subperiod::BuildAndEarn
+        }
+    }
+
+    /// How many eras remain in the subperiod to which the specified era belongs.
+    ///
+    /// The specified era is included in the count, so the last era of a subperiod returns `1`.
+    /// Since the voting subperiod always takes exactly one era, `1` is always returned for it.
+    fn current_subperiod_remaining_eras(era: EraNumber) -> EraNumber {
+        match Self::subperiod_for_era(era) {
+            Subperiod::Voting => 1,
+            Subperiod::BuildAndEarn => {
+                Self::eras_per_period().saturating_sub(Self::era_index_in_period(era))
+            }
+        }
+    }
+
+    /// Number of the cycle to which the specified era belongs.
+    ///
+    /// Cycles are numbered from `1`, so the first era belongs to the first cycle.
+    /// Eras preceding the [`Self::FIRST_ERA`] belong to cycle `0`.
+    fn cycle_number_for_era(era: EraNumber) -> u32 {
+        if era < Self::FIRST_ERA {
+            0
+        } else {
+            (era - Self::FIRST_ERA) / Self::eras_per_cycle().max(1) + 1
+        }
+    }
+}
+
+/// Asserts that the [`CycleConfiguration`] implementation is internally consistent.
+///
+/// Intended to be used in test suites of the configuration implementors, e.g.
+/// `assert_cycle_config_consistent!(MyConfig)`, or `assert_cycle_config_consistent!(MyConfig, 1_000)`
+/// to check the specified number of eras (by default, eras of two full cycles are checked).
+///
+/// Checked invariants:
+/// * configuration is valid, according to [`CycleConfiguration::validate`],
+/// * era -> block: the first & the last block of each standard era length inside a period map back
+///   to the same index via [`CycleConfiguration::era_in_period_for_block`],
+/// * era -> period: each era index is within the period bounds, repeats every period,
+///   and only the first era of the period belongs to the voting subperiod,
+/// * era + [`CycleConfiguration::current_subperiod_remaining_eras`] is the first era of the next subperiod,
+/// * cycle lengths in eras & blocks are derived consistently from the period length.
+#[macro_export]
+macro_rules! assert_cycle_config_consistent {
+    ($config:ty) => {
+        $crate::assert_cycle_config_consistent!(
+            $config,
+            <$config as $crate::dapp_staking::CycleConfiguration>::eras_per_cycle()
+                .saturating_mul(2)
+        )
+    };
+    ($config:ty, $eras:expr) => {{
+        use $crate::dapp_staking::{CycleConfiguration, Subperiod};
+
+        assert!(<$config>::validate(), "cycle configuration is invalid");
+
+        // Cycle lengths
+        assert_eq!(
+            <$config>::eras_per_cycle(),
+            <$config>::eras_per_period() * <$config>::periods_per_cycle()
+        );
+        assert_eq!(
+            <$config>::blocks_per_cycle(),
+            <$config>::blocks_per_era()
+                * <$config>::period_in_era_lengths()
+                * <$config>::periods_per_cycle()
+        );
+
+        // Era <-> block
+        let blocks_per_era = <$config>::blocks_per_era();
+        let period_start_block = blocks_per_era.saturating_mul(3).saturating_add(7);
+        for idx in 0..<$config>::period_in_era_lengths() {
+            let first_block = period_start_block + idx * blocks_per_era;
+            let last_block = first_block + blocks_per_era - 1;
+            assert_eq!(
+                <$config>::era_in_period_for_block(first_block, period_start_block),
+                idx,
+                "first block of era index {} maps to a different index",
+                idx
+            );
+            assert_eq!(
+                <$config>::era_in_period_for_block(last_block, period_start_block),
+                idx,
+                "last block of era index {} maps to a different index",
+                idx
+            );
+        }
+
+        // Era <-> period
+        let eras_per_period = <$config>::eras_per_period();
+        let first_era = <$config>::FIRST_ERA;
+        for era in first_era..first_era.saturating_add($eras) {
+            let idx = <$config>::era_index_in_period(era);
+            assert!(
+                idx < eras_per_period,
+                "era {} index out of period bounds",
+                era
+            );
+            assert_eq!(
+                <$config>::era_index_in_period(era + eras_per_period),
+                idx,
+                "era {} index doesn't repeat in the next period",
+                era
+            );
+            assert_eq!(
+                <$config>::subperiod_for_era(era) == Subperiod::Voting,
+                idx == 0,
+                "era {} has unexpected subperiod",
+                era
+            );
+
+            let remaining = <$config>::current_subperiod_remaining_eras(era);
+            assert!(remaining > 0, "era {} has no remaining eras", era);
+            assert_ne!(
+                <$config>::subperiod_for_era(era + remaining - 1),
+                <$config>::subperiod_for_era(era + remaining),
+                "era {} + remaining eras doesn't start a new subperiod",
+                era
+            );
+        }
+    }};
+}
+
+/// Generate a pseudo-random, valid cycle geometry from the `seed`, for property tests.
+///
+/// Intended to be used by the test suites of the configuration implementors, e.g. together with
+/// [`crate::assert_cycle_config_consistent`] applied to a configuration backed by the generated geometry.
+///
+/// Values are drawn from the following ranges, all inclusive:
+/// * `periods_per_cycle` - `[1, 12]`
+/// * `eras_per_voting_subperiod` - `[1, 30]`
+/// * `eras_per_build_and_earn_subperiod` - `[1, 365]`
+/// * `blocks_per_era` - `[1, 14_400]`
+///
+/// Upper bounds are chosen so the number of blocks per cycle always fits into the block number type.
+/// Same seed always produces the same geometry.
+#[cfg(feature = "std")]
+pub fn random_consistent_geometry(seed: u64) -> CycleGeometry {
+    // SplitMix64, good enough for test value generation
+    let mut state = seed;
+    let mut next_in_range = |min: u32, max: u32| -> u32 {
+        state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+
+        min + (z % u64::from(max - min + 1)) as u32
+    };
+
+    CycleGeometry {
+        periods_per_cycle: next_in_range(1, 12),
+        eras_per_voting_subperiod: next_in_range(1, 30),
+        eras_per_build_and_earn_subperiod: next_in_range(1, 365),
+        blocks_per_era: next_in_range(1, 14_400),
+    }
+}
+
+/// Concise report of a period transition, e.g. for governance dashboards.
+///
+/// See [`CycleConfiguration::period_transition_at`].
+#[derive(
+    Encode, Decode, MaxEncodedLen, Clone, Copy, RuntimeDebug, PartialEq, Eq, scale_info::TypeInfo,
+)]
+pub struct PeriodTransition {
+    /// Period which ended.
+    #[codec(compact)]
+    pub from: PeriodNumber,
+    /// Period which started.
+    #[codec(compact)]
+    pub to: PeriodNumber,
+    /// First era of the new period.
+    #[codec(compact)]
+    pub at_era: EraNumber,
+    /// Block at which the new period started.
+    #[codec(compact)]
+    pub at_block: BlockNumber,
+    /// Subperiod with which the new period started.
+    pub new_subperiod: Subperiod,
+}
+
+/// Kind of the boundary marked by an era's end, see [`CycleConfiguration::boundary_kind`].
+#[derive(
+    Encode, Decode, MaxEncodedLen, Clone, Copy, RuntimeDebug, PartialEq, Eq, scale_info::TypeInfo,
+)]
+pub enum BoundaryKind {
+    /// No boundary, nothing needs to be settled.
+    None,
+    /// Only the era ends, era rewards need to be settled.
+    EraOnly,
+    /// Both the era & the period end, era rewards & bonus rewards need to be settled.
+    EraAndPeriod,
+    /// Era, period & cycle all end, additionally inflation parameters need to be recalculated.
+    EraAndCycle,
+}
+
+/// Snapshot of the cycle configuration values.
+///
+/// Useful when the configuration needs to be passed around as a value, e.g. for off-chain analysis.
+#[derive(
+    Encode, Decode, MaxEncodedLen, Copy, Clone, RuntimeDebug, PartialEq, Eq, scale_info::TypeInfo,
+)]
+pub struct CycleGeometry {
+    /// How many different periods are there in a cycle.
+    pub periods_per_cycle: PeriodNumber,
+    /// For how many standard era lengths does the voting subperiod last.
+    pub eras_per_voting_subperiod: EraNumber,
+    /// How many standard eras are there in the build&earn subperiod.
+    pub eras_per_build_and_earn_subperiod: EraNumber,
+    /// How many blocks are there per standard era.
+    pub blocks_per_era: BlockNumber,
+}
+
+impl CycleGeometry {
+    /// Snapshot of the specified cycle configuration.
+    pub fn from_config<C: CycleConfiguration>() -> Self {
+        Self {
+            periods_per_cycle: C::periods_per_cycle(),
+            eras_per_voting_subperiod: C::eras_per_voting_subperiod(),
+            eras_per_build_and_earn_subperiod: C::eras_per_build_and_earn_subperiod(),
+            blocks_per_era: C::blocks_per_era(),
+        }
+    }
+
+    /// For how many standard era lengths do all the build&earn subperiods in a cycle last.
+    pub fn build_and_earn_eras_per_cycle(&self) -> EraNumber {
+        self.eras_per_build_and_earn_subperiod
+            .saturating_mul(self.periods_per_cycle)
+    }
+
+    /// Fields which differ between the `old` & `new` geometry, as `(field_name, old_value, new_value)` tuples.
+    ///
+    /// Fields are listed in declaration order. Empty if both are equal.
+    pub fn diff(old: &Self, new: &Self) -> Vec<(&'static str, u64, u64)> {
+        [
+            (
+                "periods_per_cycle",
+                old.periods_per_cycle,
+                new.periods_per_cycle,
+            ),
+            (
+                "eras_per_voting_subperiod",
+                old.eras_per_voting_subperiod,
+                new.eras_per_voting_subperiod,
+            ),
+            (
+                "eras_per_build_and_earn_subperiod",
+                old.eras_per_build_and_earn_subperiod,
+                new.eras_per_build_and_earn_subperiod,
+            ),
+            ("blocks_per_era", old.blocks_per_era, new.blocks_per_era),
+        ]
+        .into_iter()
+        .filter(|(_, old_value, new_value)| old_value != new_value)
+        .map(|(field, old_value, new_value)| (field, old_value.into(), new_value.into()))
+        .collect()
+    }
+}
+
+/// Estimated staker APR, assuming the cycle lasts one year and the per-era staker reward pool doesn't change.
+///
+/// Only build&earn eras are rewarded, so the yearly reward is `staker_pool_per_era` times the number of build&earn eras in a cycle.
+/// Value saturates at 100%, and zero is returned if nothing is staked. Result is rounded down.
+pub fn estimated_apr(
+    staker_pool_per_era: Balance,
+    total_staked: Balance,
+    cfg: &CycleGeometry,
+) -> Perbill {
+    if total_staked.is_zero() {
+        return Perbill::zero();
+    }
+
+    let yearly_reward =
+        staker_pool_per_era.saturating_mul(cfg.build_and_earn_eras_per_cycle().into());
+    Perbill::from_rational(yearly_reward.min(total_staked), total_staked)
+}
+
+/// Per-era staker reward pool required to achieve `target_apr` for the specified total stake.
+///
+/// Inverse of [`estimated_apr`]. Zero is returned if nothing is staked. Result is rounded down.
+pub fn pool_for_target_apr(
+    target_apr: Perbill,
+    total_staked: Balance,
+    cfg: &CycleGeometry,
+) -> Balance {
+    let reward_eras = Balance::from(cfg.build_and_earn_eras_per_cycle());
+    if total_staked.is_zero() || reward_eras.is_zero() {
+        return Balance::zero();
+    }
+
+    let yearly_reward: u128 = target_apr.mul_floor(total_staked);
+    yearly_reward / reward_eras
+}
+
+/// Human readable summary of the cycle configuration durations.
+#[derive(Copy, Clone, RuntimeDebug, PartialEq, Eq)]
+pub struct CycleHumanSummary {
+    /// Duration of a standard era, in minutes.
+    pub era_minutes: u64,
+    /// Duration of a period, in days.
+    pub period_days: u64,
+    /// Duration of a cycle, in days.
+    pub cycle_days: u64,
+}
+
+/// Reward pools allocated for a finished era.
+#[derive(
+    Encode,
+    Decode,
+    MaxEncodedLen,
+    Copy,
+    Clone,
+    Default,
+    RuntimeDebug,
+    PartialEq,
+    Eq,
+    scale_info::TypeInfo,
+)]
+pub struct RewardPools {
+    /// Reward pool for stakers.
+    #[codec(compact)]
+    pub staker: Balance,
+    /// Reward pool for dApps.
+    #[codec(compact)]
+    pub dapp: Balance,
+    /// Bonus reward pool for loyal stakers.
+    /// Allocated once per period, so it's only non-zero for the final era of a period.
+    #[codec(compact)]
+    pub bonus: Balance,
+}
+
+/// Kind of the reward pool.
+#[derive(
+    Encode, Decode, MaxEncodedLen, Clone, Copy, RuntimeDebug, PartialEq, Eq, scale_info::TypeInfo,
+)]
+pub enum RewardPoolKind {
+    /// Reward pool for stakers.
+    Staker,
+    /// Reward pool for dApps.
+    DApp,
+    /// Bonus reward pool for loyal stakers.
+    Bonus,
+}
+
+impl RewardPoolKind {
+    /// All the reward pool kinds.
+    pub fn all() -> [RewardPoolKind; 3] {
+        [Self::Staker, Self::DApp, Self::Bonus]
+    }
+
+    /// Stable label of the reward pool kind.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Staker => "staker",
+            Self::DApp => "dapp",
+            Self::Bonus => "bonus",
+        }
+    }
+}
+
+impl fmt::Display for RewardPoolKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl RewardPools {
+    /// Amount of the specified reward pool.
+    pub fn get(&self, kind: RewardPoolKind) -> Balance {
+        match kind {
+            RewardPoolKind::Staker => self.staker,
+            RewardPoolKind::DApp => self.dapp,
+            RewardPoolKind::Bonus => self.bonus,
+        }
+    }
+}
+
+/// One value of type `T` per reward pool kind.
+///
+/// Values are accessed by [`RewardPoolKind`], which avoids mismatched indexing of ad hoc arrays.
+#[derive(
+    Encode,
+    Decode,
+    MaxEncodedLen,
+    Clone,
+    Copy,
+    RuntimeDebug,
+    PartialEq,
+    Eq,
+    Default,
+    scale_info::TypeInfo,
+)]
+pub struct PerPool<T> {
+    /// Value for the staker reward pool.
+    pub staker: T,
+    /// Value for the dApp reward pool.
+    pub dapp: T,
+    /// Value for the bonus reward pool.
+    pub bonus: T,
+}
+
+impl<T> PerPool<T> {
+    /// Value for the specified reward pool kind.
+    pub fn get(&self, kind: RewardPoolKind) -> &T {
+        match kind {
+            RewardPoolKind::Staker => &self.staker,
+            RewardPoolKind::DApp => &self.dapp,
+            RewardPoolKind::Bonus => &self.bonus,
+        }
+    }
+
+    /// Mutable value for the specified reward pool kind.
+    pub fn get_mut(&mut self, kind: RewardPoolKind) -> &mut T {
+        match kind {
+            RewardPoolKind::Staker => &mut self.staker,
+            RewardPoolKind::DApp => &mut self.dapp,
+            RewardPoolKind::Bonus => &mut self.bonus,
+        }
+    }
+
+    /// Apply `f` to each of the values, keeping them assigned to the same reward pool kind.
+    pub fn map<U>(self, mut f: impl FnMut(T) -> U) -> PerPool<U> {
+        PerPool {
+            staker: f(self.staker),
+            dapp: f(self.dapp),
+            bonus: f(self.bonus),
+        }
+    }
+}
+
+/// Payload describing a single reward distribution.
+///
+/// Intended to be emitted as an event, and decoded off-chain, e.g. by indexers.
+/// Fields are only ever appended, to keep the encoding stable.
+#[derive(
+    Encode, Decode, MaxEncodedLen, Clone, Copy, RuntimeDebug, PartialEq, Eq, scale_info::TypeInfo,
+)]
+pub struct RewardDistributed<AccountId> {
+    /// Era for which the reward was distributed.
+    #[codec(compact)]
+    pub era: EraNumber,
+    /// Account which received the reward.
+    pub beneficiary: AccountId,
+    /// Distributed reward amount.
+    #[codec(compact)]
+    pub amount: Balance,
+    /// Reward pool from which the reward was distributed.
+    pub kind: RewardPoolKind,
+}
+
+/// Reason why the distributed rewards don't reconcile with the reward pools.
+#[derive(
+    Encode, Decode, MaxEncodedLen, Clone, Copy, RuntimeDebug, PartialEq, Eq, scale_info::TypeInfo,
+)]
+pub enum ReconcileError {
+    /// Distributed amount exceeds the specified reward pool.
+    PoolExceeded(RewardPoolKind),
+}
+
+/// Check that none of the `distributed` reward amounts exceed the corresponding reward `pools`.
+///
+/// Pools are checked in the [`RewardPoolKind::all`] order, and the first offending one is returned.
+pub fn reconcile(pools: &RewardPools, distributed: &RewardPools) -> Result<(), ReconcileError> {
+    match RewardPoolKind::all()
+        .into_iter()
+        .find(|kind| distributed.get(*kind) > pools.get(*kind))
+    {
+        Some(kind) => Err(ReconcileError::PoolExceeded(kind)),
+        None => Ok(()),
+    }
+}
+
+/// Fixed-size history of the reward pools of the last `K` eras.
+///
+/// Once `K` entries have been pushed, the buffer wraps around and each new entry overwrites the oldest one,
+/// so the storage footprint never grows beyond `K` entries.
+#[derive(
+    Encode, Decode, MaxEncodedLen, Clone, Copy, RuntimeDebug, PartialEq, Eq, scale_info::TypeInfo,
+)]
+pub struct PoolHistory<const K: usize> {
+    /// Ring buffer of the reward pools.
+    buf: [RewardPools; K],
+    /// Number of pushed entries. Once the buffer has wrapped around, it's kept in the `[K, 2 * K)` range,
+    /// so `head % K` is always the position of the next write, and it never overflows.
+    head: u32,
+}
+
+impl<const K: usize> Default for PoolHistory<K> {
+    fn default() -> Self {
+        Self {
+            buf: [RewardPools::default(); K],
+            head: 0,
+        }
+    }
+}
+
+impl<const K: usize> PoolHistory<K> {
+    /// Create new, empty, history.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of stored entries, never exceeding `K`.
+    pub fn len(&self) -> usize {
+        (self.head as usize).min(K)
+    }
+
+    /// `true` if no entry is stored, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.len().is_zero()
+    }
+
+    /// Push the reward pools of a new era, overwriting the oldest entry if the history is full.
+    pub fn push(&mut self, era_pools: RewardPools) {
+        if K.is_zero() {
+            return;
+        }
+
+        self.buf[self.head as usize % K] = era_pools;
+
+        self.head = self.head.saturating_add(1);
+        if self.head as usize >= 2 * K {
+            self.head = K as u32;
+        }
+    }
+
+    /// Up to `n` of the most recently pushed entries, starting with the latest one.
+    pub fn recent(&self, n: usize) -> impl Iterator<Item = &RewardPools> + '_ {
+        let head = self.head as usize;
+
+        (1..=n.min(self.len())).map(move |offset| &self.buf[(head + K - offset) % K])
+    }
+}
+
+/// Reward pools resulting from an inflation recalculation.
+#[derive(
+    Encode,
+    Decode,
+    MaxEncodedLen,
+    Copy,
+    Clone,
+    Default,
+    RuntimeDebug,
+    PartialEq,
+    Eq,
+    scale_info::TypeInfo,
+)]
+pub struct InflationSnapshot {
+    /// dApp reward pool per era.
+    #[codec(compact)]
+    pub dapp_reward_pool_per_era: Balance,
+    /// Base staker reward pool per era, provided regardless of the total value staked.
+    #[codec(compact)]
+    pub base_staker_reward_pool_per_era: Balance,
+    /// Adjustable staker reward pool per era, depending on the total value staked.
+    #[codec(compact)]
+    pub adjustable_staker_reward_pool_per_era: Balance,
+    /// Bonus reward pool per period, for loyal stakers.
+    #[codec(compact)]
+    pub bonus_reward_pool_per_period: Balance,
+    /// Total reward pools over the entire cycle.
+    pub per_cycle: RewardPools,
+}
+
+/// Inflation snapshot derived from the per-era reward pools.
+///
+/// Cycle totals are deliberately **not** `pool * eras_per_cycle`:
+/// * staker & dApp pools are scaled by the number of build&earn eras in a cycle, since voting eras aren't rewarded,
+/// * bonus pool is allocated once per period, so it's scaled by the number of periods in a cycle.
+///
+/// Entire staker pool is treated as the base staker reward pool, so the adjustable one is zero.
+/// Callers which track the base/adjustable split can overwrite the per-era staker fields, cycle totals are unaffected.
+pub fn snapshot_from_pools(pools: &RewardPools, cfg: &CycleGeometry) -> InflationSnapshot {
+    let reward_eras = Balance::from(cfg.build_and_earn_eras_per_cycle());
+    let periods = Balance::from(cfg.periods_per_cycle);
+
+    InflationSnapshot {
+        dapp_reward_pool_per_era: pools.dapp,
+        base_staker_reward_pool_per_era: pools.staker,
+        adjustable_staker_reward_pool_per_era: Balance::zero(),
+        bonus_reward_pool_per_period: pools.bonus,
+        per_cycle: RewardPools {
+            staker: pools.staker.saturating_mul(reward_eras),
+            dapp: pools.dapp.saturating_mul(reward_eras),
+            bonus: pools.bonus.saturating_mul(periods),
+        },
+    }
+}
+
+/// Record of a dApp moving between tiers, from one era to the next.
+///
+/// `None` tier means that dApp is _unassigned_, i.e. it doesn't belong to any tier.
+#[derive(
+    Encode, Decode, MaxEncodedLen, Clone, Copy, RuntimeDebug, PartialEq, Eq, scale_info::TypeInfo,
+)]
+pub struct ContractMoveRecord {
+    /// dApp which moved.
+    #[codec(compact)]
+    pub dapp: DAppId,
+    /// Era in which the dApp belongs to the new tier.
+    #[codec(compact)]
+    pub era: EraNumber,
+    /// Tier of the dApp before the move.
+    pub from_tier: Option<TierId>,
+    /// Tier of the dApp after the move.
+    pub to_tier: Option<TierId>,
+}
+
+impl ContractMoveRecord {
+    /// `Some(true)` if dApp moved into a better tier, `Some(false)` if it moved into a worse one.
+    ///
+    /// Lower tier Id means a better tier, with tier `0` being the best one.
+    /// `None` if the dApp didn't actually move, i.e. both tiers are the same,
+    /// or if the dApp is entering or leaving the tiers, i.e. either tier is unassigned.
+    pub fn is_promotion(&self) -> Option<bool> {
+        match (self.from_tier, self.to_tier) {
+            (Some(from_tier), Some(to_tier)) if from_tier != to_tier => Some(to_tier < from_tier),
+            _ => None,
+        }
+    }
+}
+
+/// Participation of a dApp in a particular era.
+#[derive(
+    Encode, Decode, MaxEncodedLen, Clone, Copy, RuntimeDebug, PartialEq, Eq, scale_info::TypeInfo,
+)]
+pub struct ContractActivity {
+    /// Id of the dApp.
+    #[codec(compact)]
+    pub dapp: DAppId,
+    /// Era to which the activity refers to.
+    #[codec(compact)]
+    pub era: EraNumber,
+    /// Total amount staked on the dApp in the era.
+    #[codec(compact)]
+    pub total_staked: Balance,
+    /// Number of accounts staking on the dApp in the era.
+    #[codec(compact)]
+    pub staker_count: u32,
+}
+
+impl ContractActivity {
+    /// `true` if the dApp was active in the era, `false` otherwise.
+    ///
+    /// dApp is considered active if it has at least one staker, and at least `min_stake` staked on it.
+    pub fn is_active(&self, min_stake: Balance) -> bool {
+        self.staker_count > 0 && self.total_staked >= min_stake
+    }
+}
+
+/// `true` if the specified `dapp` wasn't active in any of the last `window` eras, `false` otherwise.
+///
+/// The window ends with the `current_era`, and covers `window` consecutive eras. Records of other dApps,
+/// and records outside of the window are ignored. A record is considered inactive if it isn't [`ContractActivity::is_active`].
+///
+/// Gaps are treated as inactivity, i.e. an era without a record in `activity` counts as an era in which the dApp was inactive.
+/// This includes the eras after the latest record, so a dApp without any recent records is considered inactive.
+/// Consequently, an empty `activity`, or a zero `window`, is always considered inactive.
+pub fn inactive_for(
+    activity: &[ContractActivity],
+    dapp: DAppId,
+    current_era: EraNumber,
+    window: EraNumber,
+    min_stake: Balance,
+) -> bool {
+    if window.is_zero() {
+        return true;
+    }
+    let window_start = current_era.saturating_sub(window - 1);
+
+    !activity.iter().any(|record| {
+        record.dapp == dapp
+            && (window_start..=current_era).contains(&record.era)
+            && record.is_active(min_stake)
+    })
+}
+
+/// Top `n` dApps by reward, in descending order of reward.
+///
+/// Ties are broken by the ascending dApp Id. If `n` exceeds the number of dApps, all of them are returned.
+pub fn top_dapps_by_reward(rewards: &[(DAppId, Balance)], n: usize) -> Vec<(DAppId, Balance)> {
+    let mut top_dapps = rewards.to_vec();
+    top_dapps
+        .sort_by(|(id_a, reward_a), (id_b, reward_b)| reward_b.cmp(reward_a).then(id_a.cmp(id_b)));
+    top_dapps.truncate(n);
+
+    top_dapps
+}
+
+/// dApp Ids ranked by stake, in descending order, as expected by the tier assignment.
+///
+/// dApps with equal stake are ranked by the ascending dApp Id.
+pub fn rank_by_stake(dapps: &[(DAppId, Balance)]) -> Vec<DAppId> {
+    let mut ranked = dapps.to_vec();
+    ranked.sort_by(|(id_a, stake_a), (id_b, stake_b)| stake_b.cmp(stake_a).then(id_a.cmp(id_b)));
+
+    ranked.into_iter().map(|(id, _)| id).collect()
+}
+
+/// Queue of dApps waiting to enter a full tier.
+///
+/// Entries are ordered by stake in descending order, and entries with equal stake by insertion order.
+/// At most `MaxLen` dApps can be queued at once.
+#[derive(
+    Encode,
+    Decode,
+    MaxEncodedLen,
+    RuntimeDebugNoBound,
+    PartialEqNoBound,
+    EqNoBound,
+    CloneNoBound,
+    scale_info::TypeInfo,
+)]
+#[scale_info(skip_type_params(MaxLen))]
+pub struct PromotionQueue<MaxLen: Get<u32>> {
+    /// Queued `(dApp Id, stake)` entries, in promotion order.
+    entries: BoundedVec<(DAppId, Balance), MaxLen>,
+}
+
+impl<MaxLen: Get<u32>> Default for PromotionQueue<MaxLen> {
+    fn default() -> Self {
+        Self {
+            entries: BoundedVec::default(),
+        }
+    }
+}
+
+impl<MaxLen: Get<u32>> PromotionQueue<MaxLen> {
+    /// Create a new empty queue.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue the dApp with the specified stake.
+    ///
+    /// dApp is placed after all the entries with the same or higher stake.
+    /// Returns an error if the dApp is already queued, or if the queue is full.
+    pub fn push(&mut self, dapp: DAppId, stake: Balance) -> Result<(), ()> {
+        if self.position(dapp).is_some() {
+            return Err(());
+        }
+
+        let idx = self
+            .entries
+            .iter()
+            .position(|(_, queued_stake)| *queued_stake < stake)
+            .unwrap_or(self.entries.len());
+        self.entries.try_insert(idx, (dapp, stake)).map_err(|_| ())
+    }
+
+    /// Remove & return the entry with the highest stake, if any.
+    pub fn pop_highest(&mut self) -> Option<(DAppId, Balance)> {
+        if self.entries.is_empty() {
+            None
+        } else {
+            Some(self.entries.remove(0))
+        }
+    }
+
+    /// Zero-based position of the dApp in the queue, or `None` if it isn't queued.
+    pub fn position(&self, dapp: DAppId) -> Option<usize> {
+        self.entries.iter().position(|(id, _)| *id == dapp)
+    }
+
+    /// Number of queued dApps.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// `true` if no dApps are queued, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Adjust the tier entry threshold, based on the tier `occupancy` relative to the `target` occupancy.
+///
+/// If occupancy exceeds the target, the threshold is raised by `step` of the current value, making the tier harder to enter.
+/// Otherwise, if occupancy is below the target, the threshold is lowered by `step` of the current value, but never below zero.
+/// Threshold is left unchanged if occupancy is exactly at the target.
+pub fn adjust_threshold(
+    current: Balance,
+    occupancy: Perbill,
+    target: Perbill,
+    step: Perbill,
+) -> Balance {
+    let delta = step * current;
+
+    if occupancy > target {
+        current.saturating_add(delta)
+    } else if occupancy < target {
+        current.saturating_sub(delta)
+    } else {
+        current
+    }
+}
+
+/// Vesting schedule of the bonus reward earned for a period.
+///
+/// Bonus is released linearly, `released_per_era` for each era elapsed, until `total` is reached.
+#[derive(
+    Encode,
+    Decode,
+    MaxEncodedLen,
+    Copy,
+    Clone,
+    Default,
+    RuntimeDebug,
+    PartialEq,
+    Eq,
+    scale_info::TypeInfo,
+)]
+pub struct BonusVesting {
+    /// Period for which the bonus reward was earned.
+    #[codec(compact)]
+    pub period: PeriodNumber,
+    /// Total bonus reward amount to vest.
+    #[codec(compact)]
+    pub total: Balance,
+    /// Amount released per each elapsed era.
+    #[codec(compact)]
+    pub released_per_era: Balance,
+}
+
+impl BonusVesting {
+    /// Amount releasable after `eras_elapsed` eras have passed, capped at `total`.
+    pub fn releasable(&self, eras_elapsed: EraNumber) -> Balance {
+        self.released_per_era
+            .saturating_mul(eras_elapsed.into())
+            .min(self.total)
+    }
+}
+
+/// Allocate the bonus reward `pool` among the eligible stakers, proportionally to their voting stake.
+///
+/// Each allocation is rounded down, so the sum of all allocations never exceeds the `pool`.
+/// Order of stakers is preserved. If total voting stake is zero, no allocations are made.
+pub fn bonus_allocation<AccountId: Clone>(
+    pool: Balance,
+    eligible: &[(AccountId, Balance)],
+) -> Vec<(AccountId, Balance)> {
+    let total_voting_stake = eligible.iter().fold(Balance::zero(), |acc, (_, stake)| {
+        acc.saturating_add(*stake)
+    });
+    if total_voting_stake.is_zero() {
+        return Vec::new();
+    }
+
+    eligible
+        .iter()
+        .map(|(account, stake)| {
+            let allocation = Perbill::from_rational(*stake, total_voting_stake).mul_floor(pool);
+            (account.clone(), allocation)
+        })
+        .collect()
+}
+
+/// Distribute the staker reward `pool` among the stakers, proportionally to their stake.
+///
+/// Each share is computed exactly as `pool * stake / total_stake`, rounded down, leaving some rounding dust in the pool. If `reassign_dust_to_largest` is `true`,
+/// the dust is added to the share of the largest staker (the first one, in case of a tie), so the shares sum up to exactly the `pool`.
+/// Order of stakers is preserved. If total stake is zero, nothing is distributed.
+pub fn distribute_pool<AccountId: Clone>(
+    pool: Balance,
+    stakes: &[(AccountId, Balance)],
+    reassign_dust_to_largest: bool,
+) -> Vec<(AccountId, Balance)> {
+    let total_stake = stakes.iter().fold(Balance::zero(), |acc, (_, stake)| {
+        acc.saturating_add(*stake)
+    });
+    if total_stake.is_zero() {
+        return Vec::new();
+    }
+
+    let mut shares: Vec<(AccountId, Balance)> = stakes
+        .iter()
+        .map(|(account, stake)| {
+            let share =
+                multiply_by_rational_with_rounding(pool, *stake, total_stake, Rounding::Down)
+                    .unwrap_or_default();
+            (account.clone(), share)
+        })
+        .collect();
+
+    if reassign_dust_to_largest {
+        let distributed = shares.iter().fold(Balance::zero(), |acc, (_, share)| {
+            acc.saturating_add(*share)
+        });
+
+        let mut largest_idx = 0;
+        for (idx, (_, stake)) in stakes.iter().enumerate() {
+            if *stake > stakes[largest_idx].1 {
+                largest_idx = idx;
+            }
+        }
+
+        shares[largest_idx]
+            .1
+            .saturating_accrue(pool.saturating_sub(distributed));
+    }
+
+    shares
+}
+
+/// Whether the reward earned in `earned_era` can still be claimed in `now_era`.
+///
+/// Claim window stays open for `window_eras` eras after `earned_era`, boundary included.
+/// I.e. a reward earned in era `10` with a window of `5` eras can be claimed up to & including era `15`, and is forfeited afterwards.
+/// If `now_era` precedes `earned_era`, the window is considered open.
+pub fn claim_window_open(
+    earned_era: EraNumber,
+    now_era: EraNumber,
+    window_eras: EraNumber,
+) -> bool {
+    now_era.saturating_sub(earned_era) <= window_eras
+}
+
+/// Eras for which the staker can still claim rewards, in ascending order.
+///
+/// Covers eras from `last_claimed + 1` up to & excluding the `current` era, skipping
+/// eras whose claim window (see [`claim_window_open`]) has already expired.
+pub fn claimable_eras(
+    last_claimed: EraNumber,
+    current: EraNumber,
+    window: EraNumber,
+) -> impl Iterator<Item = EraNumber> {
+    let first_unclaimed = last_claimed.saturating_add(1);
+    let first_in_window = current.saturating_sub(window);
+
+    first_unclaimed.max(first_in_window)..current
+}
+
+/// Reason why a reward claim couldn't be marked by the [`ClaimTracker`].
+#[derive(
+    Encode, Decode, MaxEncodedLen, Clone, Copy, RuntimeDebug, PartialEq, Eq, scale_info::TypeInfo,
+)]
+pub enum ClaimTrackerError {
+    /// Reward for the era has already been claimed by the beneficiary.
+    AlreadyClaimed,
+    /// Tracker is full, so no new claims can be marked.
+    TooManyClaims,
+}
+
+/// Guard against double-claims, tracking up to `N` claimed `(era, beneficiary)` pairs.
+#[derive(
+    Encode, Decode, MaxEncodedLen, Clone, RuntimeDebug, PartialEq, Eq, scale_info::TypeInfo,
+)]
+pub struct ClaimTracker<AccountId, const N: u32> {
+    /// Claimed `(era, beneficiary)` pairs.
+    claimed: BoundedBTreeSet<(EraNumber, AccountId), ConstU32<N>>,
+}
+
+impl<AccountId, const N: u32> Default for ClaimTracker<AccountId, N> {
+    fn default() -> Self {
+        Self {
+            claimed: BoundedBTreeSet::new(),
+        }
+    }
+}
+
+impl<AccountId: Ord + Clone, const N: u32> ClaimTracker<AccountId, N> {
+    /// Create new, empty, tracker.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Mark the reward for the specified `era` as claimed by `who`.
+    ///
+    /// Returns an error if the reward has already been claimed, or if the tracker is full.
+    /// In both cases, the tracker is left unchanged.
+    pub fn try_mark(&mut self, era: EraNumber, who: AccountId) -> Result<(), ClaimTrackerError> {
+        match self.claimed.try_insert((era, who)) {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(ClaimTrackerError::AlreadyClaimed),
+            Err(_) => Err(ClaimTrackerError::TooManyClaims),
+        }
+    }
+
+    /// `true` if the reward for the specified `era` has already been claimed by `who`, `false` otherwise.
+    pub fn is_claimed(&self, era: EraNumber, who: &AccountId) -> bool {
+        self.claimed.contains(&(era, who.clone()))
+    }
+}
+
+/// Trait for observers (listeners) of various events related to dApp staking protocol.
+pub trait Observer {
+    /// Called in the block right before the next era starts.
+    ///
+    /// Returns the weight consumed by the call.
+    ///
+    /// # Arguments
+    /// * `next_era` - Era number of the next era.
+    fn block_before_new_era(_next_era: EraNumber) -> Weight {
+        Weight::zero()
+    }
+
+    /// Called once the reward pools for the ending era have been calculated.
+    ///
+    /// Returns the weight consumed by the call.
+    ///
+    /// # Arguments
+    /// * `era` - Era number of the ending era.
+    /// * `pools` - Final reward pools of the ending era.
+    fn on_era_reward_finalized(_era: EraNumber, _pools: &RewardPools) -> Weight {
+        Weight::zero()
+    }
+
+    /// Called right after the inflation has been recalculated, at the start of a new cycle.
+    ///
+    /// Returns the weight consumed by the call.
+    ///
+    /// # Arguments
+    /// * `cycle` - Cycle number for which the inflation was recalculated.
+    /// * `new_params` - Reward pools resulting from the recalculation.
+    fn on_inflation_recalculated(_cycle: u32, _new_params: &InflationSnapshot) -> Weight {
+        Weight::zero()
+    }
+
+    /// Called once per era, right after the dApps have been assigned into tiers.
+    ///
+    /// Returns the weight consumed by the call.
+    ///
+    /// # Arguments
+    /// * `era` - Era number of the ending era, for which the tiers were assigned.
+    /// * `assignment` - Full assignment of dApps into tiers.
+    fn on_tiers_assigned(_era: EraNumber, _assignment: &DAppTierAssignment) -> Weight {
+        Weight::zero()
+    }
+
+    /// Called on era change, in case the total staked amount has changed.
+    ///
+    /// Returns the weight consumed by the call.
+    ///
+    /// # Arguments
+    /// * `old` - Total staked amount of the ending era.
+    /// * `new` - Total staked amount of the new era.
+    fn on_total_stake_changed(_old: Balance, _new: Balance) -> Weight {
+        Weight::zero()
+    }
+}
+
+impl Observer for () {}
+
+/// Curve describing how the staker reward pool depends on the total value staked.
+pub trait RewardCurve {
+    /// Staker reward pool for the specified total value staked & total issuance.
+    fn staker_pool(total_staked: Balance, issuance: Balance) -> Balance;
+}
+
+/// Reward curve where the staker reward pool grows linearly with the staked portion of the total issuance.
+///
+/// The maximum pool, `MaxPool`, is reached once the entire issuance is staked. Zero issuance results in zero pool.
+pub struct LinearRewardCurve<MaxPool>(PhantomData<MaxPool>);
+impl<MaxPool: Get<Balance>> RewardCurve for LinearRewardCurve<MaxPool> {
+    fn staker_pool(total_staked: Balance, issuance: Balance) -> Balance {
+        if issuance.is_zero() {
+            return Balance::zero();
+        }
+
+        Perquintill::from_rational(total_staked.min(issuance), issuance) * MaxPool::get()
+    }
+}
+
+/// Clamp the reward `pool` so it never exceeds the `available_issuance`.
+///
+/// Safety rail against misconfigured reward curves, which could otherwise produce pools that cannot be paid out.
+pub fn clamp_pool_to_issuance(pool: Balance, available_issuance: Balance) -> Balance {
+    pool.min(available_issuance)
+}
+
+/// Rounding applied when calculating a share of a reward pool.
+#[derive(
+    Encode, Decode, MaxEncodedLen, Clone, Copy, RuntimeDebug, PartialEq, Eq, scale_info::TypeInfo,
+)]
+pub enum RoundingPolicy {
+    /// Round down, leaving the dust in the pool.
+    Floor,
+    /// Round to the nearest value, with exact halves rounded down.
+    Nearest,
+}
+
+/// Share of the reward `pool`, rounded down.
+pub fn share_of_pool(pool: Balance, share: Perbill) -> Balance {
+    share_of_pool_with(pool, share, RoundingPolicy::Floor)
+}
+
+/// Share of the reward `pool`, rounded according to the `policy`.
+///
+/// Rounding to the nearest value can distribute slightly more than the pool when many shares are rounded up,
+/// so [`RoundingPolicy::Floor`] should be used whenever the distributed total must never exceed the pool.
+pub fn share_of_pool_with(pool: Balance, share: Perbill, policy: RoundingPolicy) -> Balance {
+    match policy {
+        RoundingPolicy::Floor => share.mul_floor(pool),
+        RoundingPolicy::Nearest => share * pool,
+    }
+}
+
+/// Deduct the protocol `fee` from the `gross` reward, returning `(net_to_staker, fee_amount)`.
+///
+/// Fee amount is rounded down, in favor of the staker. It always holds that `net_to_staker + fee_amount == gross`.
+pub fn apply_reward_fee(gross: Balance, fee: Perbill) -> (Balance, Balance) {
+    let fee_amount = fee.mul_floor(gross);
+
+    (gross.saturating_sub(fee_amount), fee_amount)
+}
+
+/// Policy for handling the bonus rewards which were left unclaimed at the end of a period.
+#[derive(
+    Encode, Decode, MaxEncodedLen, Clone, Copy, RuntimeDebug, PartialEq, Eq, scale_info::TypeInfo,
+)]
+pub enum CarryOverPolicy {
+    /// Entire unclaimed amount is carried over to the next period.
+    Carry,
+    /// Entire unclaimed amount is burned.
+    Burn,
+    /// Specified portion of the unclaimed amount is carried over, while the rest is burned.
+    Split(Perbill),
+}
+
+/// Split the `unclaimed` bonus rewards according to the `policy`, returning `(carried, burned)`.
+///
+/// Carried amount of the [`CarryOverPolicy::Split`] is rounded down. It always holds that `carried + burned == unclaimed`.
+pub fn carry_over_bonus(unclaimed: Balance, policy: CarryOverPolicy) -> (Balance, Balance) {
+    let carried = match policy {
+        CarryOverPolicy::Carry => unclaimed,
+        CarryOverPolicy::Burn => Balance::zero(),
+        CarryOverPolicy::Split(portion) => portion.mul_floor(unclaimed),
+    };
+
+    (carried, unclaimed.saturating_sub(carried))
+}
+
+/// Simple weight accumulator, intended for observers which need to stay within a weight budget.
+///
+/// Used weight never exceeds the `limit`.
+#[derive(Copy, Clone, RuntimeDebug, PartialEq, Eq)]
+pub struct WeightMeter {
+    used: Weight,
+    limit: Weight,
+}
+
+impl WeightMeter {
+    /// Create a new meter with the specified weight `limit`.
+    pub fn new(limit: Weight) -> Self {
+        Self {
+            used: Weight::zero(),
+            limit,
+        }
+    }
+
+    /// Consume the specified weight.
+    ///
+    /// Returns `true` if the weight fits into the limit, `false` otherwise.
+    /// In the latter case, used weight is capped at the limit.
+    pub fn consume(&mut self, weight: Weight) -> bool {
+        let new_used = self.used.saturating_add(weight);
+
+        if new_used.all_lte(self.limit) {
+            self.used = new_used;
+            true
+        } else {
+            self.used = new_used.min(self.limit);
+            false
+        }
+    }
+
+    /// Weight that can still be consumed.
+    pub fn remaining(&self) -> Weight {
+        self.limit.saturating_sub(self.used)
+    }
+
+    /// Weight consumed so far.
+    pub fn used(&self) -> Weight {
+        self.used
+    }
+
+    /// Weight limit of the meter.
+    pub fn limit(&self) -> Weight {
+        self.limit
+    }
+}
+
+/// Reason why a reward payout failed.
+#[derive(
+    Encode, Decode, MaxEncodedLen, Copy, Clone, RuntimeDebug, PartialEq, Eq, scale_info::TypeInfo,
+)]
+pub enum RewardPayoutError {
+    /// Reward is below the handler's minimum payout.
+    BelowMinimum,
+    /// Reward couldn't be paid out, e.g. due to safety limits.
+    Failed,
+}
+
+/// Interface for staking reward handler.
+///
+/// Provides reward pool values for stakers - normal & bonus rewards, as well as dApp reward pool.
+/// Also provides a safe function for paying out rewards.
+///
+/// The shape of the staker reward pool curve is up to the implementation, and can be expressed via [`RewardCurve`].
+pub trait StakingRewardHandler<AccountId> {
+    /// Returns the staker reward pool & dApp reward pool for an era.
+    ///
+    /// The total staker reward pool is dynamic and depends on the total value staked, e.g. according to a [`RewardCurve`].
+    /// Implementations should use [`clamp_pool_to_issuance`] to ensure the pools never exceed the available issuance.
+    fn staker_and_dapp_reward_pools(total_value_staked: Balance) -> (Balance, Balance);
+
+    /// Returns the bonus reward pool for a period.
+    fn bonus_reward_pool() -> Balance;
+
+    /// Minimum reward amount the handler is willing to pay out.
+    ///
+    /// Can be used to avoid dust payouts, e.g. ones below the existential deposit.
+    fn minimum_payout() -> Balance {
+        0
+    }
+
+    /// Account from which the rewards are paid out, e.g. a reward pot.
+    ///
+    /// `None` if the rewards aren't paid from an account, e.g. if they are minted on demand.
+    fn reward_source() -> Option<AccountId> {
+        None
+    }
+
+    /// Attempts to pay out the rewards to the beneficiary.
+    ///
+    /// Rewards below [`Self::minimum_payout`] are rejected with [`RewardPayoutError::BelowMinimum`].
+    fn payout_reward(beneficiary: &AccountId, reward: Balance) -> Result<(), RewardPayoutError>;
+
+    /// Accrues the reward to the beneficiary, without an immediate transfer, so it can be claimed later.
+    ///
+    /// Returns the total reward accrued to the beneficiary so far, including `reward`.
+    /// By default, nothing is accrued: the reward is paid out immediately via [`Self::payout_reward`],
+    /// and only the `reward` itself is returned.
+    fn accrue_reward(
+        beneficiary: &AccountId,
+        reward: Balance,
+    ) -> Result<Balance, RewardPayoutError> {
+        Self::payout_reward(beneficiary, reward).map(|_| reward)
+    }
+
+    /// Attempts to pay out the dApp reward earned in the specified `tier` to the beneficiary.
+    ///
+    /// Implementations may apply tier-specific rules before paying out. By default, delegates to [`Self::payout_reward`].
+    fn payout_dapp_reward(
+        beneficiary: &AccountId,
+        _tier: TierId,
+        reward: Balance,
+    ) -> Result<(), RewardPayoutError> {
+        Self::payout_reward(beneficiary, reward)
+    }
+
+    /// Projected reward of a staker with `stake` amount, in an era where `total_value_staked` is staked.
+    ///
+    /// Staker receives its proportional share of the era's staker reward pool, rounded down.
+    /// Returns zero if nothing is staked.
+    fn projected_staker_reward(stake: Balance, total_value_staked: Balance) -> Balance {
+        if stake.is_zero() || total_value_staked.is_zero() {
+            return Balance::zero();
+        }
+
+        let (staker_reward_pool, _) = Self::staker_and_dapp_reward_pools(total_value_staked);
+        Perbill::from_rational(stake, total_value_staked).mul_floor(staker_reward_pool)
+    }
+
+    /// Simulates the staker reward settlement for the specified era, without mutating any state.
+    ///
+    /// Returns the reward of each staker, in the same order as `stakers`.
+    /// As long as the stakes sum up to at most `total_staked`, sum of all rewards never exceeds the era's staker reward pool.
+    fn simulate_era_settlement(
+        _era: EraNumber,
+        stakers: &[(AccountId, Balance)],
+        total_staked: Balance,
+    ) -> Vec<(AccountId, Balance)>
+    where
+        AccountId: Clone,
+    {
+        stakers
+            .iter()
+            .map(|(account, stake)| {
+                (
+                    account.clone(),
+                    Self::projected_staker_reward(*stake, total_staked),
+                )
+            })
+            .collect()
+    }
+
+    /// Settles the rewards of up to `max_eras` historical eras, starting with `start_era`, without exceeding `budget`.
+    ///
+    /// Returns the next era to process, and the weight consumed by the settlement.
+    ///
+    /// Eras are processed in ascending order, and only whole eras are settled. If the returned era equals
+    /// `start_era`, nothing was processed, e.g. because the budget couldn't cover a single era.
+    /// Callers resume the settlement by passing the returned era as `start_era` in the next call.
+    ///
+    /// By default nothing is processed.
+    fn settle_eras(
+        start_era: EraNumber,
+        _max_eras: EraNumber,
+        _budget: Weight,
+    ) -> (EraNumber, Weight) {
+        (start_era, Weight::zero())
+    }
+}
+
+/// Length of the opaque address of [`SmartContract::Other`] instance, in bytes.
+pub const OTHER_CONTRACT_ADDRESS_LEN: usize = 32;
+
+/// Maximum length of the canonical smart contract key, in bytes.
+pub const SMART_CONTRACT_KEY_LEN: u32 = 34;
+
+/// Trait defining the interface for dApp staking `smart contract types` handler.
+///
+/// It can be used to create a representation of the specified smart contract instance type.
+pub trait SmartContractHandle<AccountId> {
+    /// Create a new smart contract representation for the specified EVM address.
+    fn evm(address: H160) -> Self;
+    /// Create a new smart contract representation for the specified Wasm address.
+    fn wasm(address: AccountId) -> Self;
+}
+
+/// Kind of the virtual machine on which a smart contract is deployed.
+#[derive(
+    Encode, Decode, MaxEncodedLen, Clone, Copy, RuntimeDebug, PartialEq, Eq, scale_info::TypeInfo,
+)]
+pub enum VmKind {
+    /// Ethereum virtual machine.
+    Evm,
+    /// Wasm virtual machine.
+    Wasm,
+    /// Other virtual machine, identified by the sub-kind tag.
+    Other(u8),
+}
+
+/// Target to which a call or a reward can be dispatched, depending on the virtual machine.
+///
+/// Decoupled from [`SmartContract`] so executors can branch on the VM without depending on the staking types.
+#[derive(
+    PartialEq, Eq, Copy, Clone, Encode, Decode, RuntimeDebug, MaxEncodedLen, scale_info::TypeInfo,
+)]
+pub enum DispatchTarget<AccountId> {
+    /// Dispatch to the EVM address.
+    Evm(H160),
+    /// Dispatch to the Wasm account.
+    Wasm(AccountId),
+    /// Dispatch to the opaque address on the other VM, identified by the sub-kind tag.
+    Other(u8, [u8; OTHER_CONTRACT_ADDRESS_LEN]),
+}
+
+/// Maximum length of the [`CrossChainContractId`] key, in bytes.
+pub const CROSS_CHAIN_KEY_LEN: u32 = 32;
+
+/// Minimal, chain-agnostic descriptor of a smart contract, meant for cross-chain consumers.
+///
+/// Intentionally decoupled from XCM types, so primitives don't depend on them.
+#[derive(
+    PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, MaxEncodedLen, scale_info::TypeInfo,
+)]
+pub struct CrossChainContractId {
+    /// Virtual machine on which the contract is deployed.
+    pub vm_kind: VmKind,
+    /// Raw address bytes of the contract on the virtual machine.
+    pub key: BoundedVec<u8, ConstU32<CROSS_CHAIN_KEY_LEN>>,
+}
+
+/// Multi-VM pointer to smart contract instance.
+///
+/// New variants are only ever appended, so the existing variants keep their SCALE encoding discriminants.
+#[derive(
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Copy,
+    Clone,
+    Encode,
+    Decode,
+    RuntimeDebug,
+    MaxEncodedLen,
+    Hash,
+    scale_info::TypeInfo,
+)]
+pub enum SmartContract<AccountId> {
+    /// EVM smart contract instance.
+    Evm(H160),
+    /// Wasm smart contract instance.
+    Wasm(AccountId),
+    /// Smart contract instance on some other VM, identified by the sub-kind tag, with an opaque address.
+    ///
+    /// Address is kept as a fixed size array so the type remains `Copy` & bounded.
+    Other(u8, [u8; OTHER_CONTRACT_ADDRESS_LEN]),
+}
+
+/// Errors possible when constructing a smart contract from its raw parts.
+#[derive(RuntimeDebug, PartialEq, Eq, Clone, Copy)]
+pub enum SmartContractError {
+    /// EVM address isn't exactly 20 bytes long.
+    InvalidEvmAddressLength,
+    /// Wasm address length doesn't match the account Id length.
+    InvalidWasmAddressLength,
+    /// Wasm address bytes couldn't be decoded into an account Id.
+    InvalidWasmAddress,
+    /// Opaque address of the other VM contract isn't exactly 32 bytes long.
+    InvalidOtherAddressLength,
+}
+
+/// JSON-RPC friendly representation of a [`SmartContract`].
+///
+/// VM is one of `evm`, `wasm` or `other:<sub_kind>`, and the address is `0x` prefixed hex string.
+/// For Wasm contracts, address is the hex encoded account Id.
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct RpcSmartContract {
+    /// Virtual machine on which the smart contract is deployed.
+    pub vm: String,
+    /// Hex encoded smart contract address.
+    pub address: String,
+}
+
+/// Errors possible when converting [`RpcSmartContract`] into a [`SmartContract`].
+#[cfg(feature = "std")]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum RpcSmartContractError {
+    /// VM isn't recognized.
+    UnknownVm,
+    /// Address isn't a valid hex string.
+    InvalidHex,
+    /// Address bytes don't represent a valid smart contract address.
+    InvalidAddress(SmartContractError),
+}
+
+#[cfg(feature = "std")]
+impl<AccountId: Encode> From<SmartContract<AccountId>> for RpcSmartContract {
+    fn from(contract: SmartContract<AccountId>) -> Self {
+        let (vm, address) = match contract {
+            SmartContract::Evm(address) => ("evm".to_string(), address.as_bytes().to_vec()),
+            SmartContract::Wasm(account_id) => ("wasm".to_string(), account_id.encode()),
+            SmartContract::Other(sub_kind, address) => {
+                (format!("other:{}", sub_kind), address.to_vec())
+            }
+        };
+
+        Self {
+            vm,
+            address: sp_core::bytes::to_hex(&address, false),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<AccountId: Decode + MaxEncodedLen> TryFrom<RpcSmartContract> for SmartContract<AccountId> {
+    type Error = RpcSmartContractError;
+
+    fn try_from(contract: RpcSmartContract) -> Result<Self, Self::Error> {
+        let kind = match contract.vm.as_str() {
+            "evm" => VmKind::Evm,
+            "wasm" => VmKind::Wasm,
+            other => other
+                .strip_prefix("other:")
+                .and_then(|sub_kind| sub_kind.parse().ok())
+                .map(VmKind::Other)
+                .ok_or(RpcSmartContractError::UnknownVm)?,
+        };
+        let bytes = sp_core::bytes::from_hex(&contract.address)
+            .map_err(|_| RpcSmartContractError::InvalidHex)?;
+
+        Self::from_parts(kind, &bytes).map_err(RpcSmartContractError::InvalidAddress)
+    }
+}
+
+// TODO: remove this once dApps staking v2 has been removed.
+impl<AccountId> Default for SmartContract<AccountId> {
+    fn default() -> Self {
+        Self::evm([0x01; 20].into())
+    }
+}
+
+impl<AccountId> SmartContract<AccountId> {
+    /// `true` if this is exactly the value returned by `Default`, `false` otherwise.
+    ///
+    /// Default value is only a sentinel, and should never be persisted as a real smart contract.
+    pub fn is_default_sentinel(&self) -> bool {
+        matches!(self, Self::Evm(address) if *address == H160::repeat_byte(0x01))
+    }
+}
+
+impl<AccountId> SmartContract<AccountId> {
+    /// Numeric identifier of the smart contract variant.
+    ///
+    /// Matches the variant index used by the SCALE encoding.
+    pub fn variant_id(&self) -> u8 {
+        match self {
+            Self::Evm(_) => 0,
+            Self::Wasm(_) => 1,
+            Self::Other(..) => 2,
+        }
+    }
+
+    /// Kind of the virtual machine on which the smart contract is deployed.
+    pub fn vm_kind(&self) -> VmKind {
+        match self {
+            Self::Evm(_) => VmKind::Evm,
+            Self::Wasm(_) => VmKind::Wasm,
+            Self::Other(sub_kind, _) => VmKind::Other(*sub_kind),
+        }
+    }
+
+    /// Dispatch target of the smart contract.
+    pub fn dispatch_target(&self) -> DispatchTarget<AccountId>
+    where
+        AccountId: Clone,
+    {
+        match self {
+            Self::Evm(address) => DispatchTarget::Evm(*address),
+            Self::Wasm(account_id) => DispatchTarget::Wasm(account_id.clone()),
+            Self::Other(sub_kind, address) => DispatchTarget::Other(*sub_kind, *address),
+        }
+    }
+
+    /// EIP-55 mixed-case checksummed address of the EVM smart contract, `0x` prefixed.
+    ///
+    /// `None` for non-EVM smart contracts.
+    #[cfg(feature = "std")]
+    pub fn evm_checksummed(&self) -> Option<String> {
+        let address = match self {
+            Self::Evm(address) => address,
+            _ => return None,
+        };
+
+        let lowercase_hex: String = address
+            .as_bytes()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+        let hash = sp_io::hashing::keccak_256(lowercase_hex.as_bytes());
+
+        // Each hex character is uppercased if the matching nibble of the hash is at least 8.
+        let checksummed: String = lowercase_hex
+            .chars()
+            .enumerate()
+            .map(|(idx, character)| {
+                let nibble = if idx % 2 == 0 {
+                    hash[idx / 2] >> 4
+                } else {
+                    hash[idx / 2] & 0x0f
+                };
+
+                if nibble >= 8 {
+                    character.to_ascii_uppercase()
+                } else {
+                    character
+                }
+            })
+            .collect();
+
+        Some(format!("0x{}", checksummed))
+    }
+}
+
+impl<AccountId: Decode + MaxEncodedLen> SmartContract<AccountId> {
+    /// Create a smart contract from the VM kind & raw address bytes.
+    ///
+    /// EVM address must be exactly 20 bytes long, while Wasm address must be exactly as long as the encoded account Id.
+    /// Opaque address of other VM contracts must be exactly 32 bytes long.
+    pub fn from_parts(kind: VmKind, bytes: &[u8]) -> Result<Self, SmartContractError> {
+        match kind {
+            VmKind::Evm => {
+                if bytes.len() != H160::len_bytes() {
+                    return Err(SmartContractError::InvalidEvmAddressLength);
+                }
+                Ok(Self::Evm(H160::from_slice(bytes)))
+            }
+            VmKind::Wasm => {
+                if bytes.len() != AccountId::max_encoded_len() {
+                    return Err(SmartContractError::InvalidWasmAddressLength);
+                }
+                AccountId::decode(&mut &bytes[..])
+                    .map(Self::Wasm)
+                    .map_err(|_| SmartContractError::InvalidWasmAddress)
+            }
+            VmKind::Other(sub_kind) => bytes
+                .try_into()
+                .map(|address| Self::Other(sub_kind, address))
+                .map_err(|_| SmartContractError::InvalidOtherAddressLength),
+        }
+    }
+}
+
+impl<AccountId: AsRef<[u8; 32]>> SmartContract<AccountId> {
+    /// Canonical key of the smart contract, which can be reproduced off-chain.
+    ///
+    /// Layout is `[variant_id][address_bytes]`, where address bytes are either the 20 bytes of the EVM address,
+    /// or the 32 bytes of the Wasm contract account Id. Unlike the SCALE encoding, the layout is fixed and doesn't
+    /// depend on how the account Id type is encoded.
+    ///
+    /// For other VM contracts, layout is `[variant_id][sub_kind][address_bytes]`, with 32 opaque address bytes.
+    pub fn storage_key_bytes(&self) -> BoundedVec<u8, ConstU32<SMART_CONTRACT_KEY_LEN>> {
+        let mut key = Vec::with_capacity(SMART_CONTRACT_KEY_LEN as usize);
+        key.push(self.variant_id());
+
+        match self {
+            Self::Evm(address) => key.extend_from_slice(address.as_bytes()),
+            Self::Wasm(account_id) => key.extend_from_slice(AsRef::<[u8; 32]>::as_ref(account_id)),
+            Self::Other(sub_kind, address) => {
+                key.push(*sub_kind);
+                key.extend_from_slice(address);
+            }
+        }
+
+        // Key length never exceeds the bound, so nothing is truncated.
+        BoundedVec::truncate_from(key)
+    }
+
+    /// Cross-chain descriptor of the smart contract.
+    ///
+    /// EVM contracts use the 20 bytes of the address as the key, while Wasm contracts use the 32 bytes of the account Id.
+    /// Other VM contracts use the 32 opaque address bytes, with the sub-kind tag carried by the VM kind.
+    pub fn cross_chain_id(&self) -> CrossChainContractId {
+        let key: &[u8] = match self {
+            Self::Evm(address) => address.as_bytes(),
+            Self::Wasm(account_id) => AsRef::<[u8; 32]>::as_ref(account_id),
+            Self::Other(_, address) => address,
+        };
+
+        CrossChainContractId {
+            vm_kind: self.vm_kind(),
+            // Key length never exceeds the bound, so nothing is truncated.
+            key: BoundedVec::truncate_from(key.to_vec()),
+        }
+    }
+}
+
+/// Pack the smart contracts into a single flat buffer, for compact wire transfer.
+///
+/// Each entry is the SCALE encoded smart contract, prefixed with its compact encoded length.
+/// Entries follow each other without any additional separator or overall length prefix.
+pub fn pack_contracts<A: Encode>(contracts: &[SmartContract<A>]) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    for contract in contracts {
+        let encoded = contract.encode();
+        parity_scale_codec::Compact(encoded.len() as u32).encode_to(&mut buffer);
+        buffer.extend_from_slice(&encoded);
+    }
+
+    buffer
+}
+
+/// Unpack the smart contracts from a buffer created by [`pack_contracts`].
+///
+/// Fails if the buffer is truncated, or if any entry isn't exactly a single valid smart contract encoding.
+pub fn unpack_contracts<A: Decode>(
+    bytes: &[u8],
+) -> Result<Vec<SmartContract<A>>, parity_scale_codec::Error> {
+    let mut input = bytes;
+    let mut contracts = Vec::new();
+
+    while !input.is_empty() {
+        let len = parity_scale_codec::Compact::<u32>::decode(&mut input)?.0 as usize;
+        if input.len() < len {
+            return Err("Truncated smart contract entry".into());
+        }
+
+        let (mut entry, rest) = input.split_at(len);
+        contracts.push(parity_scale_codec::DecodeAll::decode_all(&mut entry)?);
+        input = rest;
+    }
+
+    Ok(contracts)
+}
+
+impl<AccountId> SmartContractHandle<AccountId> for SmartContract<AccountId> {
+    fn evm(address: H160) -> Self {
+        Self::Evm(address)
+    }
+
+    fn wasm(address: AccountId) -> Self {
+        Self::Wasm(address)
+    }
+}
+
+/// Registration status of a smart contract in dApp staking.
+#[derive(
+    Encode, Decode, MaxEncodedLen, Clone, Copy, RuntimeDebug, PartialEq, Eq, scale_info::TypeInfo,
+)]
+pub enum ContractRegistrationStatus {
+    /// Contract is registered and can be staked on.
+    Registered,
+    /// Contract is in the process of being unregistered.
+    Unregistering,
+    /// Contract has been unregistered.
+    Unregistered,
+}
+
+impl ContractRegistrationStatus {
+    /// `true` if transition from `from` status to `to` status is allowed, `false` otherwise.
+    ///
+    /// Allowed transitions are:
+    /// * `Registered` -> `Unregistering`
+    /// * `Unregistering` -> `Unregistered`
+    /// * `Unregistered` -> `Registered`, when a previously unregistered contract is registered again.
+    ///
+    /// All other transitions, including the ones from a status to itself, are illegal.
+    pub fn can_transition(from: &Self, to: &Self) -> bool {
+        matches!(
+            (from, to),
+            (Self::Registered, Self::Unregistering)
+                | (Self::Unregistering, Self::Unregistered)
+                | (Self::Unregistered, Self::Registered)
+        )
+    }
+}
+
+/// Used to map the dispatch origin of a smart contract call back to the smart contract.
+pub trait TryFromOrigin<AccountId>: Sized {
+    /// Smart contract which dispatched the call as `who`, or `None` if it cannot be resolved.
+    ///
+    /// By default, Wasm contracts are mapped directly from the account Id.
+    /// EVM contracts dispatch calls via an account Id derived from the EVM address, e.g. by hashing it,
+    /// which in general cannot be reversed. Therefore the default implementation returns `None` for them,
+    /// and implementations which can resolve the EVM address, e.g. via a mapping lookup, must override this method.
+    fn try_from_contract_origin(who: &AccountId, is_evm: bool) -> Option<SmartContract<AccountId>>
+    where
+        AccountId: Clone,
+    {
+        if is_evm {
+            None
+        } else {
+            Some(SmartContract::Wasm(who.clone()))
+        }
+    }
+}
+
+impl<AccountId> TryFromOrigin<AccountId> for SmartContract<AccountId> {}
+
+/// Used to resolve the account which should receive the dApp rewards of a smart contract.
+pub trait BeneficiaryResolver<AccountId> {
+    /// Reward beneficiary of the specified smart contract, or `None` if it cannot be resolved.
+    ///
+    /// Depending on the implementation, EVM contracts may resolve to a mapped substrate account.
+    fn beneficiary(contract: &SmartContract<AccountId>) -> Option<AccountId>;
+}
+
+impl<AccountId> BeneficiaryResolver<AccountId> for () {
+    fn beneficiary(_contract: &SmartContract<AccountId>) -> Option<AccountId> {
+        None
+    }
+}
+
+/// Policy restricting which kinds of smart contracts are permitted, e.g. for registration.
+#[derive(
+    Encode, Decode, MaxEncodedLen, Copy, Clone, RuntimeDebug, PartialEq, Eq, scale_info::TypeInfo,
+)]
+pub enum VmPolicy {
+    /// No restriction, contracts of any kind are permitted.
+    Both,
+    /// Only EVM smart contracts are permitted.
+    EvmOnly,
+    /// Only Wasm smart contracts are permitted.
+    WasmOnly,
+}
+
+/// `true` if the smart contract is permitted by the VM `policy`, `false` otherwise.
+pub fn vm_policy_permits<A>(policy: VmPolicy, contract: &SmartContract<A>) -> bool {
+    match policy {
+        VmPolicy::Both => true,
+        VmPolicy::EvmOnly => matches!(contract, SmartContract::Evm(_)),
+        VmPolicy::WasmOnly => matches!(contract, SmartContract::Wasm(_)),
+    }
+}
+
+/// Mode of the [`ContractFilter`].
+#[derive(
+    Encode, Decode, MaxEncodedLen, Copy, Clone, RuntimeDebug, PartialEq, Eq, scale_info::TypeInfo,
+)]
+pub enum FilterMode {
+    /// Only the listed contracts are permitted.
+    AllowList,
+    /// All contracts except the listed ones are permitted.
+    DenyList,
+}
+
+/// Allow or deny list of smart contracts, e.g. to restrict which contracts can be registered.
+///
+/// At most `N` contracts can be listed.
+#[derive(Encode, Decode, MaxEncodedLen, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct ContractFilter<A, const N: u32> {
+    /// Listed contracts.
+    allow: BoundedVec<SmartContract<A>, ConstU32<N>>,
+    /// How the listed contracts are treated.
+    mode: FilterMode,
+}
+
+impl<A: PartialEq, const N: u32> ContractFilter<A, N> {
+    /// Create a new filter, listing the specified contracts.
+    pub fn new(allow: BoundedVec<SmartContract<A>, ConstU32<N>>, mode: FilterMode) -> Self {
+        Self { allow, mode }
+    }
+
+    /// Mode of the filter.
+    pub fn mode(&self) -> FilterMode {
+        self.mode
+    }
+
+    /// `true` if the specified contract is permitted by the filter, `false` otherwise.
+    pub fn permits(&self, contract: &SmartContract<A>) -> bool {
+        let listed = self.allow.contains(contract);
+        match self.mode {
+            FilterMode::AllowList => listed,
+            FilterMode::DenyList => !listed,
+        }
+    }
+}
+
+/// Small cache keyed by smart contracts, evicting the least recently used entry once `CAP` entries are stored.
+///
+/// Intended for hot-path lookups, so entries are kept in a vector ordered by recency, from the least to the most recently used.
+/// Lookups are linear, which is fine for small capacities. Only contract equality is needed, no hashing or ordering.
+#[derive(Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct ContractLru<A, V, const CAP: usize> {
+    /// Cached entries, the least recently used one first.
+    entries: Vec<(SmartContract<A>, V)>,
+}
+
+impl<A, V, const CAP: usize> Default for ContractLru<A, V, CAP> {
+    fn default() -> Self {
+        Self {
+            entries: Vec::with_capacity(CAP),
+        }
+    }
+}
+
+impl<A: Eq, V, const CAP: usize> ContractLru<A, V, CAP> {
+    /// Create new, empty, cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of cached entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// `true` if no entry is cached, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Cached value of the specified contract, if any. A hit marks the entry as the most recently used one.
+    pub fn get(&mut self, contract: &SmartContract<A>) -> Option<&V> {
+        let idx = self.entries.iter().position(|(key, _)| key == contract)?;
+        let entry = self.entries.remove(idx);
+        self.entries.push(entry);
+
+        self.entries.last().map(|(_, value)| value)
+    }
+
+    /// Cache the value of the specified contract, as the most recently used entry.
+    ///
+    /// Existing value of the contract is replaced. Otherwise, if the cache is full, the least recently used entry is evicted.
+    pub fn put(&mut self, contract: SmartContract<A>, value: V) {
+        if CAP.is_zero() {
+            return;
+        }
+
+        if let Some(idx) = self.entries.iter().position(|(key, _)| *key == contract) {
+            self.entries.remove(idx);
+        } else if self.entries.len() >= CAP {
+            self.entries.remove(0);
+        }
+
+        self.entries.push((contract, value));
+    }
+}
+
+/// Policy describing where the dApp rewards of a smart contract should go.
+#[derive(
+    PartialEq, Eq, Copy, Clone, Encode, Decode, RuntimeDebug, MaxEncodedLen, scale_info::TypeInfo,
+)]
+pub enum ContractPayoutPolicy<AccountId> {
+    /// Rewards are paid out directly to the dApp owner.
+    Direct,
+    /// Rewards are sent to the treasury.
+    Treasury,
+    /// Rewards are paid out to the specified account.
+    Account(AccountId),
+    /// Rewards are burned.
+    Burn,
+}
+
+impl<AccountId: Clone> ContractPayoutPolicy<AccountId> {
+    /// Account which should receive the rewards according to the policy, or `None` if rewards should be burned.
+    pub fn resolve(&self, owner: &AccountId, treasury: &AccountId) -> Option<AccountId> {
+        match self {
+            Self::Direct => Some(owner.clone()),
+            Self::Treasury => Some(treasury.clone()),
+            Self::Account(account) => Some(account.clone()),
+            Self::Burn => None,
+        }
+    }
+}
+
+/// Map with smart contracts as keys.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, scale_info::TypeInfo)]
+pub struct ContractMap<AccountId: Ord, V>(BTreeMap<SmartContract<AccountId>, V>);
+
+impl<AccountId: Ord, V> Default for ContractMap<AccountId, V> {
+    fn default() -> Self {
+        Self(BTreeMap::new())
+    }
+}
+
+impl<AccountId: Ord, V> ContractMap<AccountId, V> {
+    /// Create a new empty map.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Entry of the specified smart contract, for in-place manipulation.
+    pub fn entry(
+        &mut self,
+        contract: SmartContract<AccountId>,
+    ) -> Entry<'_, SmartContract<AccountId>, V> {
+        self.0.entry(contract)
+    }
+
+    /// Value associated with the specified smart contract, if any.
+    pub fn get(&self, contract: &SmartContract<AccountId>) -> Option<&V> {
+        self.0.get(contract)
+    }
+
+    /// Insert the value for the specified smart contract, returning the previous value, if any.
+    pub fn insert(&mut self, contract: SmartContract<AccountId>, value: V) -> Option<V> {
+        self.0.insert(contract, value)
+    }
+
+    /// Number of entries in the map.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// `true` if map contains no entries, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Iterator over the entries of smart contracts deployed on the specified VM kind, ordered by contract.
+    pub fn by_vm_kind(
+        &self,
+        kind: VmKind,
+    ) -> impl Iterator<Item = (&SmartContract<AccountId>, &V)> {
+        self.0
+            .iter()
+            .filter(move |(contract, _)| contract.vm_kind() == kind)
+    }
+}
+
+/// Lazily filtered iterator over smart contracts, returned by [`SmartContractIterExt`] methods.
+pub type SmartContractFilter<I, A> = core::iter::Filter<I, fn(&SmartContract<A>) -> bool>;
+
+/// Extension trait for iterators over smart contracts, to filter them by the VM type.
+///
+/// E.g. `contracts.into_iter().only_evm()`.
+pub trait SmartContractIterExt<A>: Iterator<Item = SmartContract<A>> + Sized {
+    /// Only yield the EVM smart contracts.
+    fn only_evm(self) -> SmartContractFilter<Self, A> {
+        let predicate: fn(&SmartContract<A>) -> bool =
+            |contract| matches!(contract, SmartContract::Evm(_));
+        self.filter(predicate)
+    }
+
+    /// Only yield the Wasm smart contracts.
+    fn only_wasm(self) -> SmartContractFilter<Self, A> {
+        let predicate: fn(&SmartContract<A>) -> bool =
+            |contract| matches!(contract, SmartContract::Wasm(_));
+        self.filter(predicate)
+    }
+}
+
+impl<A, I: Iterator<Item = SmartContract<A>>> SmartContractIterExt<A> for I {}
+
+/// Reason why an account isn't eligible to stake.
+#[derive(
+    Encode, Decode, MaxEncodedLen, Clone, Copy, RuntimeDebug, PartialEq, Eq, scale_info::TypeInfo,
+)]
+pub enum StakeIneligibility {
+    /// Account isn't allowed to participate in dApp staking.
+    NotAllowed,
+}
+
+/// Used to check whether an account is allowed to participate in dApp staking or not.
+pub trait AccountCheck<AccountId> {
+    /// `true` if the account is allowed to stake, `false` otherwise.
+    fn allowed_to_stake(account: &AccountId) -> bool;
+
+    /// `Ok(())` if the account is eligible to stake, otherwise the reason why it isn't.
+    fn stake_eligibility(account: &AccountId) -> Result<(), StakeIneligibility> {
+        if Self::allowed_to_stake(account) {
+            Ok(())
+        } else {
+            Err(StakeIneligibility::NotAllowed)
+        }
+    }
+}
+
+/// Partition the accounts into the eligible & ineligible ones, according to the specified account check.
+///
+/// Eligibility of each account is checked exactly once, and the relative order of accounts is preserved.
+pub fn partition_eligible<C: AccountCheck<A>, A: Clone>(
+    accounts: &[A],
+) -> (Vec<A>, Vec<(A, StakeIneligibility)>) {
+    let mut eligible = Vec::new();
+    let mut ineligible = Vec::new();
+
+    for account in accounts {
+        match C::stake_eligibility(account) {
+            Ok(()) => eligible.push(account.clone()),
+            Err(reason) => ineligible.push((account.clone(), reason)),
+        }
+    }
+
+    (eligible, ineligible)
+}
+
+impl<AccountId> AccountCheck<AccountId> for () {
+    fn allowed_to_stake(_account: &AccountId) -> bool {
+        true
+    }
+}
+
+/// Provider of unique dApp identifiers.
+///
+/// `DAppId::MAX` serves as a sentinel value and must never be assigned to a dApp.
+pub trait DAppIdProvider {
+    /// Next free dApp Id.
+    fn next_dapp_id(&self) -> DAppId;
+
+    /// Set the next free dApp Id.
+    fn set_next_dapp_id(&self, next: DAppId);
+}
+
+/// Reserve a contiguous range of `count` dApp Ids from the provider.
+///
+/// Either the whole range is reserved, or none of it is: provider is only updated if there are enough
+/// free Ids left before the `DAppId::MAX` sentinel value. Since the provider is read & updated once,
+/// the caller must ensure no other allocation happens in between, e.g. by executing it in a single extrinsic.
+pub fn reserve_range(
+    provider: &impl DAppIdProvider,
+    count: u16,
+) -> Result<core::ops::Range<DAppId>, ()> {
+    let start = provider.next_dapp_id();
+    // MAX value must never be assigned as a dApp Id since it serves as a sentinel value.
+    // It's fine for it to be the (exclusive) end of the range though.
+    let end = start.checked_add(count).ok_or(())?;
+
+    provider.set_next_dapp_id(end);
+    Ok(start..end)
+}
+
+/// Lookup of the dApp Id ↔ smart contract mapping, in both directions.
+///
+/// Decouples the consumers from the storage layout of the registered dApps.
+pub trait DAppLookup<AccountId> {
+    /// Smart contract registered under the specified dApp Id, if any.
+    fn contract_of(id: DAppId) -> Option<SmartContract<AccountId>>;
+
+    /// dApp Id under which the specified smart contract is registered, if any.
+    fn id_of(contract: &SmartContract<AccountId>) -> Option<DAppId>;
+}
+
+impl<AccountId> DAppLookup<AccountId> for () {
+    fn contract_of(_id: DAppId) -> Option<SmartContract<AccountId>> {
+        None
+    }
+
+    fn id_of(_contract: &SmartContract<AccountId>) -> Option<DAppId> {
+        None
+    }
+}
@@ -0,0 +1,3149 @@
+// This file is part of Astar.
+
+// Copyright (C) 2019-2023 Stake Technologies Pte.Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// Astar is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Astar is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Astar. If not, see <http://www.gnu.org/licenses/>.
+
+use super::*;
+use parity_scale_codec::DecodeAll;
+use sp_runtime::AccountId32;
+use std::cell::RefCell;
+
+/// Cycle configuration used for testing.
+///
+/// Each period has `1 + 5` distinct eras, and each cycle has two periods.
+struct TestCycleConfig;
+impl CycleConfiguration for TestCycleConfig {
+    fn periods_per_cycle() -> PeriodNumber {
+        2
+    }
+
+    fn eras_per_voting_subperiod() -> EraNumber {
+        3
+    }
+
+    fn eras_per_build_and_earn_subperiod() -> EraNumber {
+        5
+    }
+
+    fn blocks_per_era() -> BlockNumber {
+        10
+    }
+}
+
+/// Degenerate cycle configuration, without any build&earn eras.
+struct DegenerateCycleConfig;
+impl CycleConfiguration for DegenerateCycleConfig {
+    fn periods_per_cycle() -> PeriodNumber {
+        2
+    }
+
+    fn eras_per_voting_subperiod() -> EraNumber {
+        3
+    }
+
+    fn eras_per_build_and_earn_subperiod() -> EraNumber {
+        0
+    }
+
+    fn blocks_per_era() -> BlockNumber {
+        10
+    }
+}
+
+/// Cycle configuration with a realistic geometry, a day long era when using 12 second blocks.
+struct DailyEraCycleConfig;
+impl CycleConfiguration for DailyEraCycleConfig {
+    fn periods_per_cycle() -> PeriodNumber {
+        4
+    }
+
+    fn eras_per_voting_subperiod() -> EraNumber {
+        10
+    }
+
+    fn eras_per_build_and_earn_subperiod() -> EraNumber {
+        81
+    }
+
+    fn blocks_per_era() -> BlockNumber {
+        7200
+    }
+}
+
+#[test]
+fn humanize_works() {
+    assert_eq!(
+        DailyEraCycleConfig::humanize(12),
+        CycleHumanSummary {
+            era_minutes: 24 * 60,
+            period_days: 91,
+            cycle_days: 364,
+        }
+    );
+
+    // Durations are rounded down
+    assert_eq!(
+        TestCycleConfig::humanize(12),
+        CycleHumanSummary {
+            era_minutes: 2,
+            period_days: 0,
+            cycle_days: 0,
+        }
+    );
+    assert_eq!(TestCycleConfig::humanize(9).era_minutes, 1);
+    assert_eq!(TestCycleConfig::humanize(0).era_minutes, 0);
+}
+
+#[test]
+fn payout_events_and_blocks_between_payouts_work() {
+    assert_eq!(
+        TestCycleConfig::payout_events_per_cycle(),
+        TestCycleConfig::eras_per_cycle()
+    );
+    assert_eq!(
+        TestCycleConfig::payout_events_per_cycle(),
+        TestCycleConfig::periods_per_cycle() * TestCycleConfig::eras_per_period()
+    );
+    assert_eq!(
+        TestCycleConfig::blocks_between_payouts(),
+        TestCycleConfig::blocks_per_era()
+    );
+    assert_eq!(
+        TestCycleConfig::blocks_between_payouts() * TestCycleConfig::cycle_in_era_lengths(),
+        TestCycleConfig::blocks_per_cycle()
+    );
+}
+
+#[test]
+fn cycle_configuration_validate_works() {
+    assert!(TestCycleConfig::eras_per_period_is_valid());
+    assert!(TestCycleConfig::validate());
+
+    // Voting era alone isn't a valid period. All the other values are non-zero,
+    // so the period length check is the one rejecting the configuration.
+    assert!(DegenerateCycleConfig::periods_per_cycle() > 0);
+    assert!(DegenerateCycleConfig::eras_per_voting_subperiod() > 0);
+    assert!(DegenerateCycleConfig::blocks_per_era() > 0);
+    assert_eq!(DegenerateCycleConfig::eras_per_period(), 1);
+    assert!(!DegenerateCycleConfig::eras_per_period_is_valid());
+    assert!(!DegenerateCycleConfig::validate());
+}
+
+/// Cycle configuration which requires a longer cycle than it provides.
+struct TooShortCycleConfig;
+impl CycleConfiguration for TooShortCycleConfig {
+    const MIN_CYCLE_BLOCKS: BlockNumber = 1_000;
+
+    fn periods_per_cycle() -> PeriodNumber {
+        TestCycleConfig::periods_per_cycle()
+    }
+
+    fn eras_per_voting_subperiod() -> EraNumber {
+        TestCycleConfig::eras_per_voting_subperiod()
+    }
+
+    fn eras_per_build_and_earn_subperiod() -> EraNumber {
+        TestCycleConfig::eras_per_build_and_earn_subperiod()
+    }
+
+    fn blocks_per_era() -> BlockNumber {
+        TestCycleConfig::blocks_per_era()
+    }
+}
+
+/// Same configuration as [`TestCycleConfig`], but with eras numbered from `0`.
+struct ZeroBasedCycleConfig;
+impl CycleConfiguration for ZeroBasedCycleConfig {
+    const FIRST_ERA: EraNumber = 0;
+
+    fn periods_per_cycle() -> PeriodNumber {
+        TestCycleConfig::periods_per_cycle()
+    }
+
+    fn eras_per_voting_subperiod() -> EraNumber {
+        TestCycleConfig::eras_per_voting_subperiod()
+    }
+
+    fn eras_per_build_and_earn_subperiod() -> EraNumber {
+        TestCycleConfig::eras_per_build_and_earn_subperiod()
+    }
+
+    fn blocks_per_era() -> BlockNumber {
+        TestCycleConfig::blocks_per_era()
+    }
+}
+
+#[test]
+fn cycle_length_at_least_works() {
+    let blocks_per_cycle = TestCycleConfig::blocks_per_cycle();
+    assert!(TestCycleConfig::cycle_length_at_least(0));
+    assert!(TestCycleConfig::cycle_length_at_least(blocks_per_cycle));
+    assert!(!TestCycleConfig::cycle_length_at_least(
+        blocks_per_cycle + 1
+    ));
+
+    // Default has no lower bound
+    assert!(TestCycleConfig::MIN_CYCLE_BLOCKS.is_zero());
+    assert!(TestCycleConfig::validate());
+
+    // Too short cycle fails validation
+    assert!(TooShortCycleConfig::blocks_per_cycle() < TooShortCycleConfig::MIN_CYCLE_BLOCKS);
+    assert!(!TooShortCycleConfig::validate());
+    assert!(DailyEraCycleConfig::cycle_length_at_least(
+        TooShortCycleConfig::MIN_CYCLE_BLOCKS
+    ));
+}
+
+#[test]
+fn assert_cycle_config_consistent_macro_works() {
+    crate::assert_cycle_config_consistent!(TestCycleConfig);
+    crate::assert_cycle_config_consistent!(DailyEraCycleConfig);
+    crate::assert_cycle_config_consistent!(TestCycleConfig, 1_000);
+}
+
+#[test]
+#[should_panic = "cycle configuration is invalid"]
+fn assert_cycle_config_consistent_macro_rejects_invalid_config() {
+    crate::assert_cycle_config_consistent!(DegenerateCycleConfig);
+}
+
+thread_local! {
+    pub(crate) static GENERATED_GEOMETRY: RefCell<CycleGeometry> =
+        RefCell::new(random_consistent_geometry(0));
+}
+
+/// Cycle configuration used for property testing, backed by the generated geometry.
+struct GeneratedCycleConfig;
+impl CycleConfiguration for GeneratedCycleConfig {
+    fn periods_per_cycle() -> PeriodNumber {
+        GENERATED_GEOMETRY.with(|v| v.borrow().periods_per_cycle)
+    }
+
+    fn eras_per_voting_subperiod() -> EraNumber {
+        GENERATED_GEOMETRY.with(|v| v.borrow().eras_per_voting_subperiod)
+    }
+
+    fn eras_per_build_and_earn_subperiod() -> EraNumber {
+        GENERATED_GEOMETRY.with(|v| v.borrow().eras_per_build_and_earn_subperiod)
+    }
+
+    fn blocks_per_era() -> BlockNumber {
+        GENERATED_GEOMETRY.with(|v| v.borrow().blocks_per_era)
+    }
+}
+
+#[test]
+fn random_consistent_geometry_is_deterministic() {
+    assert_eq!(
+        random_consistent_geometry(42),
+        random_consistent_geometry(42)
+    );
+    assert!((0..10)
+        .map(random_consistent_geometry)
+        .any(|geometry| geometry != random_consistent_geometry(0)));
+}
+
+#[test]
+fn generated_cycle_configurations_are_consistent() {
+    for seed in 0..1_000 {
+        let geometry = random_consistent_geometry(seed);
+        GENERATED_GEOMETRY.with(|v| *v.borrow_mut() = geometry);
+
+        assert!(GeneratedCycleConfig::validate(), "seed {}", seed);
+        assert_eq!(
+            CycleGeometry::from_config::<GeneratedCycleConfig>(),
+            geometry
+        );
+
+        // Blocks per cycle is computed exactly, without saturating
+        let expected_blocks_per_cycle = u64::from(geometry.blocks_per_era)
+            * u64::from(
+                geometry.eras_per_voting_subperiod + geometry.eras_per_build_and_earn_subperiod,
+            )
+            * u64::from(geometry.periods_per_cycle);
+        assert_eq!(
+            u64::from(GeneratedCycleConfig::blocks_per_cycle()),
+            expected_blocks_per_cycle,
+            "seed {}",
+            seed
+        );
+
+        // Full consistency check is more expensive, so it's only done for a subset of the configurations
+        if seed % 50 == 0 {
+            crate::assert_cycle_config_consistent!(GeneratedCycleConfig);
+        }
+    }
+}
+
+#[test]
+fn eras_to_blocks_and_blocks_to_eras_work() {
+    let blocks_per_era = TestCycleConfig::blocks_per_era();
+
+    // Exact conversions
+    assert!(TestCycleConfig::eras_to_blocks(0).is_zero());
+    assert_eq!(TestCycleConfig::eras_to_blocks(1), blocks_per_era);
+    assert_eq!(TestCycleConfig::eras_to_blocks(7), 7 * blocks_per_era);
+    assert_eq!(TestCycleConfig::blocks_to_eras(7 * blocks_per_era), 7);
+    for eras in [0, 1, 3, 100] {
+        assert_eq!(
+            TestCycleConfig::blocks_to_eras(TestCycleConfig::eras_to_blocks(eras)),
+            eras
+        );
+    }
+
+    // Non-exact conversions are rounded down
+    assert!(TestCycleConfig::blocks_to_eras(blocks_per_era - 1).is_zero());
+    assert_eq!(TestCycleConfig::blocks_to_eras(blocks_per_era + 1), 1);
+    assert_eq!(TestCycleConfig::blocks_to_eras(3 * blocks_per_era - 1), 2);
+
+    // Overflow saturates
+    assert_eq!(
+        TestCycleConfig::eras_to_blocks(EraNumber::MAX),
+        BlockNumber::MAX
+    );
+}
+
+#[test]
+fn adjusted_era_start_works() {
+    // No pause
+    assert_eq!(TestCycleConfig::adjusted_era_start(100, 0), 100);
+
+    // Regular pause
+    assert_eq!(TestCycleConfig::adjusted_era_start(100, 25), 125);
+
+    // Long pause near overflow saturates
+    assert_eq!(
+        TestCycleConfig::adjusted_era_start(100, BlockNumber::MAX - 100),
+        BlockNumber::MAX
+    );
+    assert_eq!(
+        TestCycleConfig::adjusted_era_start(100, BlockNumber::MAX - 99),
+        BlockNumber::MAX
+    );
+    assert_eq!(
+        TestCycleConfig::adjusted_era_start(BlockNumber::MAX, BlockNumber::MAX),
+        BlockNumber::MAX
+    );
+}
+
+#[test]
+fn blocks_until_next_era_and_remaining_blocks_in_era_work() {
+    let blocks_per_era = TestCycleConfig::blocks_per_era();
+    let era_start = 100;
+    let era_end = era_start + blocks_per_era - 1;
+
+    // First block of the era
+    assert_eq!(
+        TestCycleConfig::blocks_until_next_era(era_start, era_start),
+        blocks_per_era
+    );
+    assert_eq!(
+        TestCycleConfig::remaining_blocks_in_era(era_start, era_start),
+        blocks_per_era - 1
+    );
+
+    // Block before the last one
+    assert_eq!(
+        TestCycleConfig::blocks_until_next_era(era_end - 1, era_start),
+        2
+    );
+    assert_eq!(
+        TestCycleConfig::remaining_blocks_in_era(era_end - 1, era_start),
+        1
+    );
+
+    // Last block of the era, the two differ
+    assert_eq!(
+        TestCycleConfig::blocks_until_next_era(era_end, era_start),
+        1
+    );
+    assert_eq!(
+        TestCycleConfig::remaining_blocks_in_era(era_end, era_start),
+        0
+    );
+
+    // First block of the next era
+    assert_eq!(
+        TestCycleConfig::blocks_until_next_era(era_end + 1, era_start),
+        0
+    );
+    assert_eq!(
+        TestCycleConfig::remaining_blocks_in_era(era_end + 1, era_start),
+        0
+    );
+}
+
+#[test]
+fn era_start_timestamp_works() {
+    let genesis_ts_ms = 1_000_000;
+    let block_time_ms = 12_000;
+    let era_ms = u64::from(TestCycleConfig::blocks_per_era()) * block_time_ms;
+
+    // Genesis era, and the ones before it
+    assert_eq!(
+        TestCycleConfig::era_start_timestamp(1, genesis_ts_ms, block_time_ms, 1),
+        genesis_ts_ms
+    );
+    assert_eq!(
+        TestCycleConfig::era_start_timestamp(3, genesis_ts_ms, block_time_ms, 5),
+        genesis_ts_ms
+    );
+
+    // Second era starts after the (longer) voting era
+    let voting_lengths = u64::from(TestCycleConfig::eras_per_voting_subperiod());
+    assert_eq!(
+        TestCycleConfig::era_start_timestamp(2, genesis_ts_ms, block_time_ms, 1),
+        genesis_ts_ms + voting_lengths * era_ms
+    );
+    assert_eq!(
+        TestCycleConfig::era_start_timestamp(4, genesis_ts_ms, block_time_ms, 1),
+        genesis_ts_ms + (voting_lengths + 2) * era_ms
+    );
+
+    // Next period starts after the full period length
+    let next_period_era = 1 + TestCycleConfig::eras_per_period();
+    let period_lengths = u64::from(TestCycleConfig::period_in_era_lengths());
+    assert_eq!(
+        TestCycleConfig::era_start_timestamp(next_period_era, genesis_ts_ms, block_time_ms, 1),
+        genesis_ts_ms + period_lengths * era_ms
+    );
+    assert_eq!(
+        TestCycleConfig::era_start_timestamp(next_period_era + 1, genesis_ts_ms, block_time_ms, 1),
+        genesis_ts_ms + (period_lengths + voting_lengths) * era_ms
+    );
+
+    // Genesis era which isn't the first era of a period,
+    // four build&earn eras & the next voting era elapse
+    assert_eq!(
+        TestCycleConfig::era_start_timestamp(next_period_era + 1, genesis_ts_ms, block_time_ms, 3),
+        genesis_ts_ms + (4 + voting_lengths) * era_ms
+    );
+}
+
+#[test]
+fn is_last_era_of_period_and_cycle_work() {
+    let eras_per_period = TestCycleConfig::eras_per_period();
+    let eras_per_cycle = TestCycleConfig::eras_per_cycle();
+
+    // Walk over a full cycle
+    let cycle_eras = 1..=eras_per_cycle;
+    assert_eq!(
+        cycle_eras
+            .clone()
+            .filter(|era| TestCycleConfig::is_last_era_of_period(*era))
+            .count(),
+        TestCycleConfig::periods_per_cycle() as usize
+    );
+    assert_eq!(
+        cycle_eras
+            .filter(|era| TestCycleConfig::is_last_era_of_cycle(*era))
+            .count(),
+        1
+    );
+
+    // Boundaries
+    assert!(!TestCycleConfig::is_last_era_of_period(1));
+    assert!(TestCycleConfig::is_last_era_of_period(eras_per_period));
+    assert!(!TestCycleConfig::is_last_era_of_period(eras_per_period + 1));
+    assert!(!TestCycleConfig::is_last_era_of_cycle(eras_per_period));
+    assert!(TestCycleConfig::is_last_era_of_cycle(eras_per_cycle));
+    assert!(!TestCycleConfig::is_last_era_of_cycle(eras_per_cycle + 1));
+    assert!(TestCycleConfig::is_last_era_of_cycle(2 * eras_per_cycle));
+}
+
+#[test]
+fn first_block_of_era_from_works() {
+    let genesis_block = 5;
+    let blocks_per_era = TestCycleConfig::blocks_per_era();
+    let voting_length = TestCycleConfig::eras_per_voting_subperiod() * blocks_per_era;
+
+    assert_eq!(
+        TestCycleConfig::first_block_of_era_from(1, genesis_block),
+        Some(genesis_block)
+    );
+    assert_eq!(
+        TestCycleConfig::first_block_of_era_from(2, genesis_block),
+        Some(genesis_block + voting_length)
+    );
+    assert_eq!(
+        TestCycleConfig::first_block_of_era_from(3, genesis_block),
+        Some(genesis_block + voting_length + blocks_per_era)
+    );
+
+    // Next period
+    let next_period_era = 1 + TestCycleConfig::eras_per_period();
+    assert_eq!(
+        TestCycleConfig::first_block_of_era_from(next_period_era, genesis_block),
+        Some(genesis_block + TestCycleConfig::period_in_era_lengths() * blocks_per_era)
+    );
+
+    // Overflow
+    assert!(TestCycleConfig::first_block_of_era_from(2, BlockNumber::MAX).is_none());
+}
+
+#[test]
+fn first_era_base_is_respected() {
+    assert_eq!(TestCycleConfig::FIRST_ERA, 1);
+    assert_eq!(ZeroBasedCycleConfig::FIRST_ERA, 0);
+
+    let genesis_block = 5;
+    let blocks_per_era = TestCycleConfig::blocks_per_era();
+    let voting_length = TestCycleConfig::eras_per_voting_subperiod() * blocks_per_era;
+    let eras_per_period = TestCycleConfig::eras_per_period();
+    let eras_per_cycle = TestCycleConfig::eras_per_cycle();
+
+    // First era starts at genesis, and the second one after the voting subperiod
+    assert_eq!(
+        TestCycleConfig::first_block_of_era_from(1, genesis_block),
+        Some(genesis_block)
+    );
+    assert_eq!(
+        TestCycleConfig::first_block_of_era_from(2, genesis_block),
+        Some(genesis_block + voting_length)
+    );
+    assert_eq!(
+        ZeroBasedCycleConfig::first_block_of_era_from(0, genesis_block),
+        Some(genesis_block)
+    );
+    assert_eq!(
+        ZeroBasedCycleConfig::first_block_of_era_from(1, genesis_block),
+        Some(genesis_block + voting_length)
+    );
+
+    // Period & cycle boundaries, one-based
+    assert_eq!(TestCycleConfig::subperiod_for_era(1), Subperiod::Voting);
+    assert!(TestCycleConfig::is_last_era_of_period(eras_per_period));
+    assert!(TestCycleConfig::is_last_era_of_cycle(eras_per_cycle));
+    assert_eq!(TestCycleConfig::cycle_number_for_era(1), 1);
+    assert_eq!(TestCycleConfig::boundary_kind(0), BoundaryKind::None);
+
+    // Period & cycle boundaries, zero-based
+    assert_eq!(
+        ZeroBasedCycleConfig::subperiod_for_era(0),
+        Subperiod::Voting
+    );
+    assert!(ZeroBasedCycleConfig::is_last_era_of_period(
+        eras_per_period - 1
+    ));
+    assert!(!ZeroBasedCycleConfig::is_last_era_of_period(
+        eras_per_period
+    ));
+    assert!(ZeroBasedCycleConfig::is_last_era_of_cycle(
+        eras_per_cycle - 1
+    ));
+    assert!(!ZeroBasedCycleConfig::is_last_era_of_cycle(eras_per_cycle));
+    assert_eq!(ZeroBasedCycleConfig::cycle_number_for_era(0), 1);
+    assert_eq!(
+        ZeroBasedCycleConfig::cycle_number_for_era(eras_per_cycle),
+        2
+    );
+    assert_eq!(
+        ZeroBasedCycleConfig::boundary_kind(0),
+        BoundaryKind::EraOnly
+    );
+
+    // Zero-based era `n` behaves exactly like the one-based era `n + 1`
+    for era in 0..2 * eras_per_cycle {
+        assert_eq!(
+            ZeroBasedCycleConfig::era_index_in_period(era),
+            TestCycleConfig::era_index_in_period(era + 1)
+        );
+        assert_eq!(
+            ZeroBasedCycleConfig::boundary_kind(era),
+            TestCycleConfig::boundary_kind(era + 1)
+        );
+        assert_eq!(
+            ZeroBasedCycleConfig::cycle_number_for_era(era),
+            TestCycleConfig::cycle_number_for_era(era + 1)
+        );
+        assert_eq!(
+            ZeroBasedCycleConfig::first_block_of_era_from(era, genesis_block),
+            TestCycleConfig::first_block_of_era_from(era + 1, genesis_block)
+        );
+    }
+
+    crate::assert_cycle_config_consistent!(ZeroBasedCycleConfig);
+}
+
+#[test]
+fn era_block_table_works() {
+    let genesis_block = 5;
+
+    for from_era in [1, 2, 6, 7, 13] {
+        let count = 3 * TestCycleConfig::eras_per_period();
+        let table = TestCycleConfig::era_block_table(from_era, count, genesis_block);
+        assert_eq!(table.len(), count as usize);
+
+        // Table matches the per-era calculation, element by element
+        for (idx, (era, first_block)) in table.into_iter().enumerate() {
+            assert_eq!(era, from_era + idx as EraNumber);
+            assert_eq!(
+                TestCycleConfig::first_block_of_era_from(era, genesis_block),
+                Some(first_block)
+            );
+        }
+    }
+
+    // Empty table
+    assert!(TestCycleConfig::era_block_table(1, 0, genesis_block).is_empty());
+
+    // Table stops early on overflow
+    let genesis_block = BlockNumber::MAX - TestCycleConfig::blocks_per_era();
+    assert_eq!(
+        TestCycleConfig::era_block_table(1, 5, genesis_block),
+        vec![(1, genesis_block)]
+    );
+}
+
+#[test]
+fn bonus_requires_full_voting_defaults_to_true() {
+    assert!(TestCycleConfig::bonus_requires_full_voting());
+    assert!(DailyEraCycleConfig::bonus_requires_full_voting());
+}
+
+#[test]
+fn period_number_for_era_works() {
+    let eras_per_period = TestCycleConfig::eras_per_period();
+
+    assert_eq!(TestCycleConfig::period_number_for_era(0), 0);
+    assert_eq!(TestCycleConfig::period_number_for_era(1), 1);
+    assert_eq!(TestCycleConfig::period_number_for_era(eras_per_period), 1);
+    assert_eq!(
+        TestCycleConfig::period_number_for_era(eras_per_period + 1),
+        2
+    );
+
+    // Zero-based eras
+    assert_eq!(ZeroBasedCycleConfig::period_number_for_era(0), 1);
+    assert_eq!(
+        ZeroBasedCycleConfig::period_number_for_era(eras_per_period),
+        2
+    );
+}
+
+#[test]
+fn period_transition_at_works() {
+    let genesis_block = 5;
+    let eras_per_period = TestCycleConfig::eras_per_period();
+
+    for period in 1..=4 {
+        let era = period * eras_per_period + 1;
+        let block = TestCycleConfig::first_block_of_era_from(era, genesis_block).unwrap();
+
+        let transition = TestCycleConfig::period_transition_at(era, block);
+        assert_eq!(
+            transition,
+            PeriodTransition {
+                from: period,
+                to: period + 1,
+                at_era: era,
+                at_block: block,
+                new_subperiod: Subperiod::Voting,
+            }
+        );
+
+        // Fields match the geometry helpers
+        assert_eq!(
+            transition.from,
+            TestCycleConfig::period_number_for_era(era - 1)
+        );
+        assert_eq!(transition.to, TestCycleConfig::period_number_for_era(era));
+        assert!(TestCycleConfig::is_last_era_of_period(era - 1));
+        assert!(TestCycleConfig::era_index_in_period(transition.at_era).is_zero());
+        assert_eq!(
+            transition.new_subperiod,
+            TestCycleConfig::subperiod_for_era(era)
+        );
+    }
+}
+
+#[test]
+fn percentage_of_cycle_elapsed_works() {
+    type Cfg = TestCycleConfig;
+    let genesis_block = 100;
+    let blocks_per_cycle = Cfg::blocks_per_cycle();
+    assert_eq!(blocks_per_cycle, 160);
+
+    // Start of the cycle
+    assert!(Cfg::percentage_of_cycle_elapsed(1, 100, 100, genesis_block).is_zero());
+
+    // Inside the voting subperiod era, and inside a build&earn era
+    assert_eq!(
+        Cfg::percentage_of_cycle_elapsed(1, 115, 100, genesis_block),
+        Perbill::from_rational(15_u32, blocks_per_cycle)
+    );
+    assert_eq!(
+        Cfg::percentage_of_cycle_elapsed(4, 155, 150, genesis_block),
+        Perbill::from_rational(55_u32, blocks_per_cycle)
+    );
+
+    // Middle of the cycle, start of the second period
+    assert_eq!(
+        Cfg::percentage_of_cycle_elapsed(7, 180, 180, genesis_block),
+        Perbill::from_percent(50)
+    );
+
+    // End of the cycle, last block of the last era is still below the full cycle
+    let last_block = Cfg::percentage_of_cycle_elapsed(12, 259, 250, genesis_block);
+    assert_eq!(
+        last_block,
+        Perbill::from_rational(159_u32, blocks_per_cycle)
+    );
+    assert!(last_block < Perbill::one());
+
+    // Next cycle starts from zero, while the old cycle's last era is clamped to the full cycle
+    assert!(Cfg::percentage_of_cycle_elapsed(13, 260, 260, genesis_block).is_zero());
+    assert_eq!(
+        Cfg::percentage_of_cycle_elapsed(12, 260, 250, genesis_block),
+        Perbill::one()
+    );
+    assert_eq!(
+        Cfg::percentage_of_cycle_elapsed(12, 1_000, 250, genesis_block),
+        Perbill::one()
+    );
+
+    // Delayed era start is accounted for
+    assert_eq!(
+        Cfg::percentage_of_cycle_elapsed(13, 275, 270, genesis_block),
+        Perbill::from_rational(5_u32, blocks_per_cycle)
+    );
+
+    // Cycle can't start before the genesis block
+    assert_eq!(
+        Cfg::percentage_of_cycle_elapsed(2, 130, 120, genesis_block),
+        Perbill::from_rational(30_u32, blocks_per_cycle)
+    );
+
+    // Eras before the first era, or blocks before the cycle start
+    assert!(Cfg::percentage_of_cycle_elapsed(0, 500, 100, genesis_block).is_zero());
+    assert!(Cfg::percentage_of_cycle_elapsed(1, 50, 100, genesis_block).is_zero());
+}
+
+#[test]
+fn is_era_in_cycle_works() {
+    let eras_per_cycle = TestCycleConfig::eras_per_cycle();
+
+    for cycle in 1..=3 {
+        let first_era = (cycle - 1) * eras_per_cycle + 1;
+        let last_era = cycle * eras_per_cycle;
+
+        // First & last era of the cycle
+        assert!(TestCycleConfig::is_era_in_cycle(first_era, cycle));
+        assert!(TestCycleConfig::is_era_in_cycle(last_era, cycle));
+
+        // Eras of the adjacent cycles
+        assert!(!TestCycleConfig::is_era_in_cycle(first_era - 1, cycle));
+        assert!(!TestCycleConfig::is_era_in_cycle(last_era + 1, cycle));
+        assert!(TestCycleConfig::is_era_in_cycle(last_era + 1, cycle + 1));
+    }
+}
+
+#[test]
+fn boundary_kind_works() {
+    let eras_per_period = TestCycleConfig::eras_per_period();
+    let eras_per_cycle = TestCycleConfig::eras_per_cycle();
+
+    assert_eq!(TestCycleConfig::boundary_kind(0), BoundaryKind::None);
+
+    // Walk over two full cycles
+    for era in 1..=2 * eras_per_cycle {
+        let expected = if era % eras_per_cycle == 0 {
+            BoundaryKind::EraAndCycle
+        } else if era % eras_per_period == 0 {
+            BoundaryKind::EraAndPeriod
+        } else {
+            BoundaryKind::EraOnly
+        };
+        assert_eq!(TestCycleConfig::boundary_kind(era), expected, "era {}", era);
+    }
+
+    // Explicit boundaries
+    assert_eq!(
+        TestCycleConfig::boundary_kind(eras_per_period),
+        BoundaryKind::EraAndPeriod
+    );
+    assert_eq!(
+        TestCycleConfig::boundary_kind(eras_per_cycle),
+        BoundaryKind::EraAndCycle
+    );
+    assert_eq!(
+        TestCycleConfig::boundary_kind(eras_per_cycle + 1),
+        BoundaryKind::EraOnly
+    );
+}
+
+#[test]
+fn subperiod_for_era_follows_period_layout() {
+    let eras_per_period = TestCycleConfig::eras_per_period();
+
+    for period in 0..3 {
+        let first_era = 1 + period * eras_per_period;
+        assert_eq!(
+            TestCycleConfig::subperiod_for_era(first_era),
+            Subperiod::Voting
+        );
+
+        for era in first_era + 1..first_era + eras_per_period {
+            assert_eq!(
+                TestCycleConfig::subperiod_for_era(era),
+                Subperiod::BuildAndEarn
+            );
+        }
+    }
+}
+
+#[test]
+fn blocks_per_cycle_capped_works() {
+    let blocks_per_cycle = TestCycleConfig::blocks_per_cycle();
+    assert_eq!(blocks_per_cycle, 160);
+
+    // Cap above the config value
+    assert_eq!(
+        TestCycleConfig::blocks_per_cycle_capped(blocks_per_cycle + 1),
+        blocks_per_cycle
+    );
+    assert_eq!(
+        TestCycleConfig::blocks_per_cycle_capped(BlockNumber::MAX),
+        blocks_per_cycle
+    );
+    assert_eq!(
+        TestCycleConfig::blocks_per_cycle_capped(blocks_per_cycle),
+        blocks_per_cycle
+    );
+
+    // Cap below the config value
+    assert_eq!(
+        TestCycleConfig::blocks_per_cycle_capped(blocks_per_cycle - 1),
+        blocks_per_cycle - 1
+    );
+    assert_eq!(TestCycleConfig::blocks_per_cycle_capped(0), 0);
+}
+
+#[test]
+fn eras_in_range_works() {
+    // Valid range
+    assert_eq!(TestCycleConfig::eras_in_range(3, 8), Some(5));
+    assert_eq!(
+        TestCycleConfig::eras_in_range(0, EraNumber::MAX),
+        Some(EraNumber::MAX)
+    );
+
+    // Empty range
+    assert_eq!(TestCycleConfig::eras_in_range(7, 7), Some(0));
+
+    // Inverted range
+    assert_eq!(TestCycleConfig::eras_in_range(8, 3), None);
+    assert_eq!(TestCycleConfig::eras_in_range(EraNumber::MAX, 0), None);
+}
+
+#[test]
+fn validate_period_transition_works() {
+    // Normal increment
+    assert_eq!(
+        TestCycleConfig::validate_period_transition(3, 4, false),
+        Ok(())
+    );
+    assert_eq!(
+        TestCycleConfig::validate_period_transition(3, 4, true),
+        Ok(())
+    );
+
+    // Invalid skip without force
+    assert_eq!(
+        TestCycleConfig::validate_period_transition(3, 5, false),
+        Err(())
+    );
+
+    // Forced skip
+    assert_eq!(
+        TestCycleConfig::validate_period_transition(3, 5, true),
+        Ok(())
+    );
+
+    // Same or previous period is never valid
+    for forced in [false, true] {
+        assert_eq!(
+            TestCycleConfig::validate_period_transition(3, 3, forced),
+            Err(())
+        );
+        assert_eq!(
+            TestCycleConfig::validate_period_transition(3, 2, forced),
+            Err(())
+        );
+    }
+    assert_eq!(
+        TestCycleConfig::validate_period_transition(PeriodNumber::MAX, 0, false),
+        Err(())
+    );
+}
+
+#[test]
+fn era_in_period_for_block_works() {
+    let period_start_block = 1_000;
+    let blocks_per_era = TestCycleConfig::blocks_per_era();
+
+    // Period start
+    assert_eq!(
+        TestCycleConfig::era_in_period_for_block(period_start_block, period_start_block),
+        0
+    );
+
+    // Mid-era
+    assert_eq!(
+        TestCycleConfig::era_in_period_for_block(
+            period_start_block + blocks_per_era / 2,
+            period_start_block
+        ),
+        0
+    );
+    assert_eq!(
+        TestCycleConfig::era_in_period_for_block(
+            period_start_block + 3 * blocks_per_era + blocks_per_era / 2,
+            period_start_block
+        ),
+        3
+    );
+
+    // Last block of the period
+    let period_length = TestCycleConfig::period_in_era_lengths() * blocks_per_era;
+    assert_eq!(
+        TestCycleConfig::era_in_period_for_block(
+            period_start_block + period_length - 1,
+            period_start_block
+        ),
+        TestCycleConfig::period_in_era_lengths() - 1
+    );
+
+    // Block before the period start
+    assert_eq!(
+        TestCycleConfig::era_in_period_for_block(period_start_block - 1, period_start_block),
+        0
+    );
+}
+
+#[test]
+fn current_subperiod_remaining_eras_works() {
+    // Voting subperiod always takes a single era.
+    assert_eq!(TestCycleConfig::current_subperiod_remaining_eras(1), 1);
+    assert_eq!(TestCycleConfig::current_subperiod_remaining_eras(7), 1);
+
+    // Build&earn subperiod of the first period spans eras 2 to 6, inclusive.
+    let build_and_earn_eras = TestCycleConfig::eras_per_build_and_earn_subperiod();
+    for (offset, era) in (2..=6).enumerate() {
+        assert_eq!(
+            TestCycleConfig::current_subperiod_remaining_eras(era),
+            build_and_earn_eras - offset as EraNumber
+        );
+    }
+
+    // Must agree with the subperiod the era belongs to.
+    for era in 1..=3 * TestCycleConfig::eras_per_cycle() {
+        let remaining = TestCycleConfig::current_subperiod_remaining_eras(era);
+        let last_era = era + remaining - 1;
+        assert_eq!(
+            TestCycleConfig::subperiod_for_era(era),
+            TestCycleConfig::subperiod_for_era(last_era)
+        );
+        assert_ne!(
+            TestCycleConfig::subperiod_for_era(last_era),
+            TestCycleConfig::subperiod_for_era(last_era + 1)
+        );
+    }
+}
+
+#[test]
+fn cycle_number_for_era_works() {
+    let eras_per_cycle = TestCycleConfig::eras_per_cycle();
+
+    assert_eq!(TestCycleConfig::cycle_number_for_era(0), 0);
+    assert_eq!(TestCycleConfig::cycle_number_for_era(1), 1);
+    assert_eq!(TestCycleConfig::cycle_number_for_era(eras_per_cycle), 1);
+    assert_eq!(TestCycleConfig::cycle_number_for_era(eras_per_cycle + 1), 2);
+    assert_eq!(TestCycleConfig::cycle_number_for_era(3 * eras_per_cycle), 3);
+}
+
+#[test]
+fn smart_contract_storage_key_bytes_layout() {
+    let evm_address = H160::repeat_byte(0xAB);
+    let evm_contract = SmartContract::<AccountId32>::Evm(evm_address);
+    let evm_key = evm_contract.storage_key_bytes();
+    assert_eq!(evm_key.len(), 21);
+    assert_eq!(evm_key[0], 0);
+    assert_eq!(&evm_key[1..], evm_address.as_bytes());
+
+    let wasm_account = AccountId32::new([0xCD; 32]);
+    let wasm_contract = SmartContract::<AccountId32>::Wasm(wasm_account.clone());
+    let wasm_key = wasm_contract.storage_key_bytes();
+    assert_eq!(wasm_key.len(), 33);
+    assert_eq!(wasm_key[0], 1);
+    assert_eq!(&wasm_key[1..], AsRef::<[u8; 32]>::as_ref(&wasm_account));
+
+    // Variant Id matches the SCALE encoding discriminant.
+    assert_eq!(evm_contract.encode()[0], evm_contract.variant_id());
+    assert_eq!(wasm_contract.encode()[0], wasm_contract.variant_id());
+}
+
+#[test]
+fn smart_contract_storage_key_bytes_are_canonical() {
+    let contract_1 = SmartContract::<AccountId32>::Wasm(AccountId32::new([1; 32]));
+    let contract_2 = SmartContract::<AccountId32>::Wasm(AccountId32::new([1; 32]));
+    assert_eq!(
+        contract_1.storage_key_bytes(),
+        contract_2.storage_key_bytes()
+    );
+
+    // Different variants with the same leading bytes never collide.
+    let evm_contract = SmartContract::<AccountId32>::Evm(H160::repeat_byte(1));
+    assert_ne!(
+        evm_contract.storage_key_bytes(),
+        contract_1.storage_key_bytes()
+    );
+}
+
+/// Staking reward handler used for testing, with a fixed staker reward pool.
+struct TestRewardHandler;
+impl TestRewardHandler {
+    const STAKER_POOL: Balance = 1_000_000;
+}
+impl StakingRewardHandler<u32> for TestRewardHandler {
+    fn staker_and_dapp_reward_pools(_total_value_staked: Balance) -> (Balance, Balance) {
+        (Self::STAKER_POOL, 500_000)
+    }
+
+    fn bonus_reward_pool() -> Balance {
+        100_000
+    }
+
+    fn payout_reward(_beneficiary: &u32, _reward: Balance) -> Result<(), RewardPayoutError> {
+        Ok(())
+    }
+}
+
+struct TinyPoolRewardHandler;
+impl StakingRewardHandler<u32> for TinyPoolRewardHandler {
+    fn staker_and_dapp_reward_pools(_total_value_staked: Balance) -> (Balance, Balance) {
+        (2, 1)
+    }
+
+    fn bonus_reward_pool() -> Balance {
+        1
+    }
+
+    fn payout_reward(_beneficiary: &u32, _reward: Balance) -> Result<(), RewardPayoutError> {
+        Ok(())
+    }
+}
+
+struct MinimumPayoutRewardHandler;
+impl MinimumPayoutRewardHandler {
+    const MINIMUM_PAYOUT: Balance = 100;
+}
+impl StakingRewardHandler<u32> for MinimumPayoutRewardHandler {
+    fn staker_and_dapp_reward_pools(_total_value_staked: Balance) -> (Balance, Balance) {
+        (1_000_000, 500_000)
+    }
+
+    fn bonus_reward_pool() -> Balance {
+        100_000
+    }
+
+    fn minimum_payout() -> Balance {
+        Self::MINIMUM_PAYOUT
+    }
+
+    fn payout_reward(_beneficiary: &u32, reward: Balance) -> Result<(), RewardPayoutError> {
+        if reward < Self::minimum_payout() {
+            Err(RewardPayoutError::BelowMinimum)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[test]
+fn minimum_payout_works() {
+    // Default has no minimum
+    assert!(TestRewardHandler::minimum_payout().is_zero());
+    assert_eq!(TestRewardHandler::payout_reward(&1, 1), Ok(()));
+
+    // Dust reward is rejected
+    let minimum = MinimumPayoutRewardHandler::minimum_payout();
+    assert_eq!(minimum, MinimumPayoutRewardHandler::MINIMUM_PAYOUT);
+    assert_eq!(
+        MinimumPayoutRewardHandler::payout_reward(&1, minimum - 1),
+        Err(RewardPayoutError::BelowMinimum)
+    );
+
+    // Minimum or more is paid out
+    assert_eq!(
+        MinimumPayoutRewardHandler::payout_reward(&1, minimum),
+        Ok(())
+    );
+    assert_eq!(
+        MinimumPayoutRewardHandler::payout_reward(&1, minimum + 1),
+        Ok(())
+    );
+}
+
+struct PotRewardHandler;
+impl PotRewardHandler {
+    const POT: u32 = 1337;
+}
+impl StakingRewardHandler<u32> for PotRewardHandler {
+    fn staker_and_dapp_reward_pools(_total_value_staked: Balance) -> (Balance, Balance) {
+        (1_000_000, 500_000)
+    }
+
+    fn bonus_reward_pool() -> Balance {
+        100_000
+    }
+
+    fn reward_source() -> Option<u32> {
+        Some(Self::POT)
+    }
+
+    fn payout_reward(_beneficiary: &u32, _reward: Balance) -> Result<(), RewardPayoutError> {
+        Ok(())
+    }
+}
+
+struct SettlingRewardHandler;
+impl SettlingRewardHandler {
+    const WEIGHT_PER_ERA: Weight = Weight::from_parts(10, 1);
+}
+impl StakingRewardHandler<u32> for SettlingRewardHandler {
+    fn staker_and_dapp_reward_pools(_total_value_staked: Balance) -> (Balance, Balance) {
+        (1_000_000, 500_000)
+    }
+
+    fn bonus_reward_pool() -> Balance {
+        100_000
+    }
+
+    fn payout_reward(_beneficiary: &u32, _reward: Balance) -> Result<(), RewardPayoutError> {
+        Ok(())
+    }
+
+    fn settle_eras(
+        start_era: EraNumber,
+        max_eras: EraNumber,
+        budget: Weight,
+    ) -> (EraNumber, Weight) {
+        let mut next_era = start_era;
+        let mut consumed_weight = Weight::zero();
+
+        while next_era.saturating_sub(start_era) < max_eras {
+            let new_consumed_weight = consumed_weight.saturating_add(Self::WEIGHT_PER_ERA);
+            if new_consumed_weight.any_gt(budget) {
+                break;
+            }
+
+            consumed_weight = new_consumed_weight;
+            next_era = next_era.saturating_add(1);
+        }
+
+        (next_era, consumed_weight)
+    }
+}
+
+#[test]
+fn settle_eras_works() {
+    let per_era = SettlingRewardHandler::WEIGHT_PER_ERA;
+
+    // Default processes nothing
+    assert_eq!(
+        TestRewardHandler::settle_eras(3, 10, Weight::MAX),
+        (3, Weight::zero())
+    );
+
+    // Bounded by the number of eras
+    assert_eq!(
+        SettlingRewardHandler::settle_eras(3, 2, Weight::MAX),
+        (5, per_era.saturating_mul(2))
+    );
+
+    // Bounded by the budget
+    let budget = per_era
+        .saturating_mul(3)
+        .saturating_add(Weight::from_parts(5, 0));
+    let (next_era, consumed_weight) = SettlingRewardHandler::settle_eras(3, 10, budget);
+    assert_eq!(next_era, 6);
+    assert_eq!(consumed_weight, per_era.saturating_mul(3));
+    assert!(consumed_weight.all_lte(budget));
+
+    // Resume from the returned era
+    assert_eq!(
+        SettlingRewardHandler::settle_eras(next_era, 10, budget),
+        (9, per_era.saturating_mul(3))
+    );
+
+    // Budget doesn't cover a single era
+    assert_eq!(
+        SettlingRewardHandler::settle_eras(3, 10, Weight::from_parts(9, 1)),
+        (3, Weight::zero())
+    );
+}
+
+thread_local! {
+    pub(crate) static PAID_OUT: RefCell<Vec<(u32, Balance)>> = RefCell::new(Vec::new());
+}
+
+/// Reward handler used for testing, which halves the dApp rewards of the lowest tier.
+struct TieredRewardHandler;
+impl TieredRewardHandler {
+    const ADJUSTED_TIER: TierId = 3;
+}
+impl StakingRewardHandler<u32> for TieredRewardHandler {
+    fn staker_and_dapp_reward_pools(_total_value_staked: Balance) -> (Balance, Balance) {
+        (1_000_000, 500_000)
+    }
+
+    fn bonus_reward_pool() -> Balance {
+        100_000
+    }
+
+    fn payout_reward(beneficiary: &u32, reward: Balance) -> Result<(), RewardPayoutError> {
+        PAID_OUT.with(|v| v.borrow_mut().push((*beneficiary, reward)));
+        Ok(())
+    }
+
+    fn payout_dapp_reward(
+        beneficiary: &u32,
+        tier: TierId,
+        reward: Balance,
+    ) -> Result<(), RewardPayoutError> {
+        let reward = if tier == Self::ADJUSTED_TIER {
+            reward / 2
+        } else {
+            reward
+        };
+        Self::payout_reward(beneficiary, reward)
+    }
+}
+
+#[test]
+fn payout_dapp_reward_works() {
+    // Default delegates to the regular payout
+    assert_eq!(TestRewardHandler::payout_dapp_reward(&1, 0, 100), Ok(()));
+    assert_eq!(
+        MinimumPayoutRewardHandler::payout_dapp_reward(&1, 0, 1),
+        Err(RewardPayoutError::BelowMinimum)
+    );
+
+    // Only the specific tier's payout is adjusted
+    for tier in 0..TieredRewardHandler::ADJUSTED_TIER {
+        assert_eq!(
+            TieredRewardHandler::payout_dapp_reward(&7, tier, 100),
+            Ok(())
+        );
+    }
+    assert_eq!(
+        TieredRewardHandler::payout_dapp_reward(&7, TieredRewardHandler::ADJUSTED_TIER, 100),
+        Ok(())
+    );
+
+    PAID_OUT.with(|v| {
+        assert_eq!(*v.borrow(), vec![(7, 100), (7, 100), (7, 100), (7, 50)]);
+    });
+}
+
+thread_local! {
+    pub(crate) static ACCRUED_REWARDS: RefCell<BTreeMap<u32, Balance>> = RefCell::new(BTreeMap::new());
+}
+
+/// Reward handler used for testing, which accrues the rewards instead of paying them out.
+struct AccruingRewardHandler;
+impl StakingRewardHandler<u32> for AccruingRewardHandler {
+    fn staker_and_dapp_reward_pools(_total_value_staked: Balance) -> (Balance, Balance) {
+        (1_000_000, 500_000)
+    }
+
+    fn bonus_reward_pool() -> Balance {
+        100_000
+    }
+
+    fn payout_reward(_beneficiary: &u32, _reward: Balance) -> Result<(), RewardPayoutError> {
+        Ok(())
+    }
+
+    fn accrue_reward(beneficiary: &u32, reward: Balance) -> Result<Balance, RewardPayoutError> {
+        ACCRUED_REWARDS.with(|v| {
+            let mut accrued = v.borrow_mut();
+            let total = accrued.entry(*beneficiary).or_default();
+            *total = total.checked_add(reward).ok_or(RewardPayoutError::Failed)?;
+            Ok(*total)
+        })
+    }
+}
+
+#[test]
+fn accrue_reward_works() {
+    // Default pays out immediately, only returning the reward
+    assert_eq!(TestRewardHandler::accrue_reward(&1, 100), Ok(100));
+    assert_eq!(TestRewardHandler::accrue_reward(&1, 50), Ok(50));
+    assert_eq!(
+        MinimumPayoutRewardHandler::accrue_reward(&1, 1),
+        Err(RewardPayoutError::BelowMinimum)
+    );
+
+    // Rewards accumulate across calls, per beneficiary
+    assert_eq!(AccruingRewardHandler::accrue_reward(&1, 100), Ok(100));
+    assert_eq!(AccruingRewardHandler::accrue_reward(&2, 30), Ok(30));
+    assert_eq!(AccruingRewardHandler::accrue_reward(&1, 50), Ok(150));
+    assert_eq!(AccruingRewardHandler::accrue_reward(&1, 0), Ok(150));
+    assert_eq!(AccruingRewardHandler::accrue_reward(&2, 70), Ok(100));
+
+    // Overflow is rejected, and the accrued total is kept
+    assert_eq!(
+        AccruingRewardHandler::accrue_reward(&1, Balance::MAX),
+        Err(RewardPayoutError::Failed)
+    );
+    assert_eq!(AccruingRewardHandler::accrue_reward(&1, 0), Ok(150));
+}
+
+#[test]
+fn reward_source_works() {
+    // Default is mint-on-demand
+    assert_eq!(TestRewardHandler::reward_source(), None);
+
+    assert_eq!(
+        PotRewardHandler::reward_source(),
+        Some(PotRewardHandler::POT)
+    );
+}
+
+#[test]
+fn simulate_era_settlement_works() {
+    let stakers = vec![(1, 300), (2, 700), (3, 1)];
+    let total_staked = 1_000 + 1;
+
+    // Each reward is rounded down
+    let settlement = TestRewardHandler::simulate_era_settlement(1, &stakers, total_staked);
+    assert_eq!(settlement, vec![(1, 299_700), (2, 699_300), (3, 999)]);
+
+    let reward_sum: Balance = settlement.iter().map(|(_, reward)| reward).sum();
+    assert_eq!(reward_sum, 999_999);
+    assert!(reward_sum <= TestRewardHandler::STAKER_POOL);
+
+    // Rounding to the nearest value would pay out `1` to each staker, exceeding the pool of `2`
+    let stakers = vec![(1, 1), (2, 1), (3, 1)];
+    assert_eq!(TinyPoolRewardHandler::projected_staker_reward(1, 3), 0);
+    assert_eq!(TinyPoolRewardHandler::projected_staker_reward(2, 3), 1);
+    assert_eq!(
+        TinyPoolRewardHandler::simulate_era_settlement(1, &stakers, 3),
+        vec![(1, 0), (2, 0), (3, 0)]
+    );
+
+    // Nothing staked, nothing to reward.
+    assert_eq!(
+        TestRewardHandler::simulate_era_settlement(1, &[(1, 300)], 0),
+        vec![(1, 0)]
+    );
+    assert!(TestRewardHandler::simulate_era_settlement(1, &[], total_staked).is_empty());
+}
+
+#[test]
+fn claimable_eras_works() {
+    // Fresh staker
+    assert_eq!(
+        claimable_eras(0, 5, 10).collect::<Vec<_>>(),
+        vec![1, 2, 3, 4]
+    );
+
+    // Partially claimed
+    assert_eq!(claimable_eras(2, 5, 10).collect::<Vec<_>>(), vec![3, 4]);
+
+    // Caught-up staker
+    assert_eq!(claimable_eras(4, 5, 10).count(), 0);
+    assert_eq!(claimable_eras(5, 5, 10).count(), 0);
+    assert_eq!(claimable_eras(7, 5, 10).count(), 0);
+
+    // Window truncates old eras
+    assert_eq!(
+        claimable_eras(0, 20, 3).collect::<Vec<_>>(),
+        vec![17, 18, 19]
+    );
+    for era in claimable_eras(0, 20, 3) {
+        assert!(claim_window_open(era, 20, 3));
+    }
+    assert!(!claim_window_open(16, 20, 3));
+
+    // Iterator is lazy, even for a huge range
+    assert_eq!(
+        claimable_eras(0, EraNumber::MAX, EraNumber::MAX).next(),
+        Some(1)
+    );
+}
+
+#[test]
+fn claim_tracker_works() {
+    let mut tracker = ClaimTracker::<u32, 3>::new();
+    assert!(!tracker.is_claimed(1, &1));
+
+    // First claim succeeds
+    assert_eq!(tracker.try_mark(1, 1), Ok(()));
+    assert!(tracker.is_claimed(1, &1));
+
+    // Same beneficiary in another era, or another beneficiary in the same era, are distinct claims
+    assert!(!tracker.is_claimed(2, &1));
+    assert!(!tracker.is_claimed(1, &2));
+    assert_eq!(tracker.try_mark(2, 1), Ok(()));
+    assert_eq!(tracker.try_mark(1, 2), Ok(()));
+
+    // Duplicate claim is rejected, and the tracker is left unchanged
+    let snapshot = tracker.clone();
+    assert_eq!(
+        tracker.try_mark(1, 1),
+        Err(ClaimTrackerError::AlreadyClaimed)
+    );
+    assert_eq!(tracker, snapshot);
+
+    // Full tracker still detects duplicates, but rejects new claims
+    assert_eq!(
+        tracker.try_mark(2, 1),
+        Err(ClaimTrackerError::AlreadyClaimed)
+    );
+    assert_eq!(
+        tracker.try_mark(3, 1),
+        Err(ClaimTrackerError::TooManyClaims)
+    );
+    assert!(!tracker.is_claimed(3, &1));
+    assert_eq!(tracker, snapshot);
+}
+
+#[test]
+fn claim_tracker_encoding_round_trip_works() {
+    let mut tracker = ClaimTracker::<u32, 3>::new();
+    assert_eq!(tracker.try_mark(5, 7), Ok(()));
+
+    let decoded = ClaimTracker::<u32, 3>::decode(&mut &tracker.encode()[..]).ok();
+    assert_eq!(decoded, Some(tracker.clone()));
+    assert!(tracker.encoded_size() <= ClaimTracker::<u32, 3>::max_encoded_len());
+}
+
+#[test]
+fn bonus_vesting_releasable_works() {
+    let vesting = BonusVesting {
+        period: 3,
+        total: 1_000,
+        released_per_era: 150,
+    };
+
+    // Linear growth before the cap
+    for eras_elapsed in 0..=6 {
+        assert_eq!(
+            vesting.releasable(eras_elapsed),
+            150 * Balance::from(eras_elapsed)
+        );
+    }
+
+    // Capped at total afterwards, even after many eras
+    assert_eq!(vesting.releasable(7), vesting.total);
+    assert_eq!(vesting.releasable(1_000), vesting.total);
+    assert_eq!(vesting.releasable(EraNumber::MAX), vesting.total);
+
+    // Huge release rate must not overflow
+    let vesting = BonusVesting {
+        released_per_era: Balance::MAX,
+        ..vesting
+    };
+    assert_eq!(vesting.releasable(0), 0);
+    assert_eq!(vesting.releasable(EraNumber::MAX), vesting.total);
+}
+
+#[test]
+fn estimated_apr_works() {
+    let cfg = CycleGeometry::from_config::<TestCycleConfig>();
+    assert_eq!(cfg.build_and_earn_eras_per_cycle(), 10);
+
+    // 10 reward eras, 100 per era, 10_000 staked => 10%
+    assert_eq!(estimated_apr(100, 10_000, &cfg), Perbill::from_percent(10));
+
+    // Saturates at 100%, and nothing staked means no APR
+    assert_eq!(
+        estimated_apr(10_000, 10_000, &cfg),
+        Perbill::from_percent(100)
+    );
+    assert_eq!(estimated_apr(100, 0, &cfg), Perbill::zero());
+}
+
+#[test]
+fn cycle_geometry_diff_works() {
+    let old = CycleGeometry::from_config::<TestCycleConfig>();
+    assert!(CycleGeometry::diff(&old, &old).is_empty());
+
+    // Single field changed
+    let new = CycleGeometry {
+        blocks_per_era: 12,
+        ..old
+    };
+    assert_eq!(
+        CycleGeometry::diff(&old, &new),
+        vec![("blocks_per_era", 10, 12)]
+    );
+
+    // Multiple fields changed, in declaration order
+    let new = CycleGeometry {
+        periods_per_cycle: 4,
+        eras_per_build_and_earn_subperiod: 7,
+        ..old
+    };
+    assert_eq!(
+        CycleGeometry::diff(&old, &new),
+        vec![
+            ("periods_per_cycle", 2, 4),
+            ("eras_per_build_and_earn_subperiod", 5, 7)
+        ]
+    );
+}
+
+#[test]
+fn pool_for_target_apr_works() {
+    let cfg = CycleGeometry::from_config::<TestCycleConfig>();
+
+    // Exact inverse of the simple case
+    assert_eq!(
+        pool_for_target_apr(Perbill::from_percent(10), 10_000, &cfg),
+        100
+    );
+
+    // Nothing staked, no pool required
+    assert!(pool_for_target_apr(Perbill::from_percent(10), 0, &cfg).is_zero());
+
+    // Feeding the result back recovers approximately the target APR
+    let total_staked: Balance = 123_456_789_000_000_000_000_000;
+    for percent in [1, 5, 8, 13, 50, 100] {
+        let target_apr = Perbill::from_percent(percent);
+        let pool = pool_for_target_apr(target_apr, total_staked, &cfg);
+        let apr = estimated_apr(pool, total_staked, &cfg);
+
+        // Round-down means the target is never exceeded
+        assert!(apr <= target_apr);
+        assert!(target_apr.deconstruct() - apr.deconstruct() <= 1);
+    }
+}
+
+#[test]
+fn claim_window_open_works() {
+    let earned_era = 10;
+    let window_eras = 5;
+
+    // Open windows, boundary included
+    for now_era in earned_era..=earned_era + window_eras {
+        assert!(claim_window_open(earned_era, now_era, window_eras));
+    }
+
+    // Exactly expired
+    assert!(!claim_window_open(
+        earned_era,
+        earned_era + window_eras + 1,
+        window_eras
+    ));
+
+    // Long expired
+    assert!(!claim_window_open(earned_era, EraNumber::MAX, window_eras));
+
+    // Era from the future is considered open, even with an empty window
+    assert!(claim_window_open(earned_era, earned_era - 1, window_eras));
+    assert!(claim_window_open(earned_era, 0, 0));
+
+    // Empty window is only open in the earned era
+    assert!(claim_window_open(earned_era, earned_era, 0));
+    assert!(!claim_window_open(earned_era, earned_era + 1, 0));
+}
+
+#[test]
+fn contract_map_by_vm_kind_works() {
+    let evm_contracts: Vec<SmartContract<u32>> = (1..=3)
+        .map(|x| SmartContract::Evm(H160::repeat_byte(x)))
+        .collect();
+    let wasm_contracts: Vec<SmartContract<u32>> = (1..=4).map(SmartContract::Wasm).collect();
+
+    let mut map = ContractMap::new();
+    assert!(map.is_empty());
+
+    // Insert mixed-kind contracts
+    for (idx, contract) in evm_contracts
+        .iter()
+        .zip(wasm_contracts.iter())
+        .flat_map(|(evm, wasm)| [evm, wasm])
+        .enumerate()
+    {
+        assert!(map.insert(*contract, idx).is_none());
+    }
+    map.insert(wasm_contracts[3], 100);
+    assert_eq!(map.len(), evm_contracts.len() + wasm_contracts.len());
+
+    // Overwrite & entry manipulation
+    assert_eq!(map.insert(evm_contracts[0], 7), Some(0));
+    *map.entry(wasm_contracts[0]).or_insert(0) += 10;
+    assert_eq!(map.get(&evm_contracts[0]), Some(&7));
+    assert_eq!(map.get(&wasm_contracts[0]), Some(&11));
+    assert!(map.get(&SmartContract::Wasm(5)).is_none());
+
+    // Filter by kind
+    let evm: Vec<_> = map
+        .by_vm_kind(VmKind::Evm)
+        .map(|(contract, _)| *contract)
+        .collect();
+    assert_eq!(evm, evm_contracts);
+
+    let wasm: Vec<_> = map
+        .by_vm_kind(VmKind::Wasm)
+        .map(|(contract, _)| *contract)
+        .collect();
+    assert_eq!(wasm, wasm_contracts);
+    assert!(map
+        .by_vm_kind(VmKind::Wasm)
+        .all(|(contract, _)| contract.vm_kind() == VmKind::Wasm));
+}
+
+thread_local! {
+    pub(crate) static INFLATION_RECALCULATED: RefCell<Option<(u32, InflationSnapshot)>> = RefCell::new(None);
+}
+
+/// Observer used for testing, records the inflation snapshot it receives.
+struct TestObserver;
+impl Observer for TestObserver {
+    fn on_inflation_recalculated(cycle: u32, new_params: &InflationSnapshot) -> Weight {
+        INFLATION_RECALCULATED.with(|v| *v.borrow_mut() = Some((cycle, *new_params)));
+        Weight::from_parts(13, 0)
+    }
+}
+
+#[test]
+fn weight_meter_works() {
+    let limit = Weight::from_parts(100, 10);
+    let mut meter = WeightMeter::new(limit);
+    assert_eq!(meter.used(), Weight::zero());
+    assert_eq!(meter.remaining(), limit);
+    assert_eq!(meter.limit(), limit);
+
+    // Consume within the limit
+    assert!(meter.consume(Weight::from_parts(60, 4)));
+    assert_eq!(meter.used(), Weight::from_parts(60, 4));
+    assert_eq!(meter.remaining(), Weight::from_parts(40, 6));
+
+    // Consume exactly up to the limit
+    assert!(meter.consume(Weight::from_parts(40, 0)));
+    assert_eq!(meter.used(), Weight::from_parts(100, 4));
+
+    // Consuming past the limit fails, and used weight is capped
+    assert!(!meter.consume(Weight::from_parts(1, 1)));
+    assert_eq!(meter.used(), Weight::from_parts(100, 5));
+    assert!(!meter.consume(Weight::from_parts(u64::MAX, u64::MAX)));
+    assert_eq!(meter.used(), limit);
+    assert_eq!(meter.remaining(), Weight::zero());
+
+    // Nothing more can be consumed, except zero weight
+    assert!(!meter.consume(Weight::from_parts(1, 0)));
+    assert!(meter.consume(Weight::zero()));
+    assert_eq!(meter.used(), limit);
+}
+
+#[test]
+fn observer_on_inflation_recalculated_works() {
+    // Default implementation is a no-op
+    assert_eq!(
+        <() as Observer>::on_inflation_recalculated(1, &InflationSnapshot::default()),
+        Weight::zero()
+    );
+
+    let snapshot = InflationSnapshot {
+        dapp_reward_pool_per_era: 100,
+        base_staker_reward_pool_per_era: 200,
+        adjustable_staker_reward_pool_per_era: 300,
+        bonus_reward_pool_per_period: 400,
+        per_cycle: RewardPools {
+            staker: 5_000,
+            dapp: 1_000,
+            bonus: 800,
+        },
+    };
+    assert!(INFLATION_RECALCULATED.with(|v| v.borrow().is_none()));
+
+    assert_eq!(
+        TestObserver::on_inflation_recalculated(3, &snapshot),
+        Weight::from_parts(13, 0)
+    );
+    assert_eq!(
+        INFLATION_RECALCULATED.with(|v| *v.borrow()),
+        Some((3, snapshot))
+    );
+}
+
+#[test]
+fn smart_contract_from_parts_works() {
+    // EVM
+    let evm_address = H160::repeat_byte(0x07);
+    assert_eq!(
+        SmartContract::<AccountId32>::from_parts(VmKind::Evm, evm_address.as_bytes()),
+        Ok(SmartContract::Evm(evm_address))
+    );
+
+    // Wasm
+    let account_id = AccountId32::new([0x09; 32]);
+    assert_eq!(
+        SmartContract::<AccountId32>::from_parts(VmKind::Wasm, AsRef::<[u8]>::as_ref(&account_id)),
+        Ok(SmartContract::Wasm(account_id))
+    );
+
+    // Round trip via storage key bytes
+    let contract = SmartContract::Wasm(AccountId32::new([0x0A; 32]));
+    let key = contract.storage_key_bytes();
+    assert_eq!(
+        SmartContract::<AccountId32>::from_parts(contract.vm_kind(), &key[1..]),
+        Ok(contract)
+    );
+}
+
+#[test]
+fn smart_contract_from_parts_with_invalid_length_fails() {
+    for len in [0, 19, 21, 32] {
+        assert_eq!(
+            SmartContract::<AccountId32>::from_parts(VmKind::Evm, &vec![1; len]),
+            Err(SmartContractError::InvalidEvmAddressLength)
+        );
+    }
+
+    for len in [0, 20, 31, 33] {
+        assert_eq!(
+            SmartContract::<AccountId32>::from_parts(VmKind::Wasm, &vec![1; len]),
+            Err(SmartContractError::InvalidWasmAddressLength)
+        );
+    }
+
+    // Account Id length is taken into account
+    assert_eq!(
+        SmartContract::<u64>::from_parts(VmKind::Wasm, &[2; 8]),
+        Ok(SmartContract::Wasm(u64::from_le_bytes([2; 8])))
+    );
+    assert_eq!(
+        SmartContract::<u64>::from_parts(VmKind::Wasm, &[2; 32]),
+        Err(SmartContractError::InvalidWasmAddressLength)
+    );
+}
+
+#[test]
+fn reward_pool_kind_labels_are_stable() {
+    let all = RewardPoolKind::all();
+
+    // Every variant is covered
+    for kind in all {
+        match kind {
+            RewardPoolKind::Staker | RewardPoolKind::DApp | RewardPoolKind::Bonus => (),
+        }
+    }
+    assert!(all.contains(&RewardPoolKind::Staker));
+    assert!(all.contains(&RewardPoolKind::DApp));
+    assert!(all.contains(&RewardPoolKind::Bonus));
+
+    // Labels are stable
+    let labels: Vec<_> = all.iter().map(|kind| kind.as_str()).collect();
+    assert_eq!(labels, vec!["staker", "dapp", "bonus"]);
+
+    for kind in all {
+        assert_eq!(kind.to_string(), kind.as_str());
+    }
+}
+
+/// Account check used for testing, blocks all accounts divisible by 3.
+struct TestAccountCheck;
+impl AccountCheck<u32> for TestAccountCheck {
+    fn allowed_to_stake(account: &u32) -> bool {
+        account % 3 != 0
+    }
+}
+
+#[test]
+fn partition_eligible_works() {
+    let accounts: Vec<u32> = (1..=10).collect();
+
+    let (eligible, ineligible) = partition_eligible::<TestAccountCheck, _>(&accounts);
+    assert_eq!(eligible, vec![1, 2, 4, 5, 7, 8, 10]);
+    assert_eq!(
+        ineligible,
+        vec![
+            (3, StakeIneligibility::NotAllowed),
+            (6, StakeIneligibility::NotAllowed),
+            (9, StakeIneligibility::NotAllowed)
+        ]
+    );
+
+    // Default check allows everyone
+    let (eligible, ineligible) = partition_eligible::<(), _>(&accounts);
+    assert_eq!(eligible, accounts);
+    assert!(ineligible.is_empty());
+
+    // Nothing to partition
+    let (eligible, ineligible) = partition_eligible::<TestAccountCheck, u32>(&[]);
+    assert!(eligible.is_empty() && ineligible.is_empty());
+}
+
+#[test]
+fn contract_move_record_is_promotion_works() {
+    let record = ContractMoveRecord {
+        dapp: 1,
+        era: 7,
+        from_tier: Some(2),
+        to_tier: Some(1),
+    };
+
+    // Promotion
+    assert_eq!(record.is_promotion(), Some(true));
+
+    // Demotion
+    let record = ContractMoveRecord {
+        from_tier: Some(1),
+        to_tier: Some(3),
+        ..record
+    };
+    assert_eq!(record.is_promotion(), Some(false));
+
+    // Same tier
+    let record = ContractMoveRecord {
+        from_tier: Some(1),
+        to_tier: Some(1),
+        ..record
+    };
+    assert_eq!(record.is_promotion(), None);
+
+    // Entering from unassigned
+    let record = ContractMoveRecord {
+        from_tier: None,
+        to_tier: Some(0),
+        ..record
+    };
+    assert_eq!(record.is_promotion(), None);
+
+    // Leaving into unassigned
+    let record = ContractMoveRecord {
+        from_tier: Some(0),
+        to_tier: None,
+        ..record
+    };
+    assert_eq!(record.is_promotion(), None);
+}
+
+#[test]
+fn smart_contract_dispatch_target_works() {
+    let evm_address = H160::repeat_byte(0x03);
+    assert_eq!(
+        SmartContract::<AccountId32>::Evm(evm_address).dispatch_target(),
+        DispatchTarget::Evm(evm_address)
+    );
+
+    let account_id = AccountId32::new([0x04; 32]);
+    assert_eq!(
+        SmartContract::Wasm(account_id.clone()).dispatch_target(),
+        DispatchTarget::Wasm(account_id)
+    );
+}
+
+/// Maximum staker pool used for testing reward curves.
+struct TestMaxPool;
+impl Get<Balance> for TestMaxPool {
+    fn get() -> Balance {
+        1_000_000
+    }
+}
+
+/// Reward curve used for testing, linear up to half of the issuance staked, and capped afterwards.
+struct CappedRewardCurve;
+impl RewardCurve for CappedRewardCurve {
+    fn staker_pool(total_staked: Balance, issuance: Balance) -> Balance {
+        let half_issuance = issuance / 2;
+        LinearRewardCurve::<TestMaxPool>::staker_pool(total_staked.min(half_issuance), issuance)
+    }
+}
+
+#[test]
+fn reward_curves_work() {
+    type Linear = LinearRewardCurve<TestMaxPool>;
+    let issuance = 10_000;
+
+    // Zero stake & zero issuance
+    assert!(Linear::staker_pool(0, issuance).is_zero());
+    assert!(Linear::staker_pool(100, 0).is_zero());
+    assert!(CappedRewardCurve::staker_pool(100, 0).is_zero());
+
+    // Both curves are the same up to the cap
+    for total_staked in [1_000, 2_500, 5_000] {
+        assert_eq!(
+            Linear::staker_pool(total_staked, issuance),
+            CappedRewardCurve::staker_pool(total_staked, issuance)
+        );
+    }
+    assert_eq!(Linear::staker_pool(2_500, issuance), 250_000);
+
+    // Beyond the cap, linear curve keeps growing while the capped one doesn't
+    assert_eq!(Linear::staker_pool(7_500, issuance), 750_000);
+    assert_eq!(CappedRewardCurve::staker_pool(7_500, issuance), 500_000);
+
+    // Linear curve never exceeds the max pool
+    assert_eq!(Linear::staker_pool(issuance, issuance), TestMaxPool::get());
+    assert_eq!(
+        Linear::staker_pool(issuance * 2, issuance),
+        TestMaxPool::get()
+    );
+}
+
+#[test]
+fn clamp_pool_to_issuance_works() {
+    let available_issuance = 1_000;
+
+    // Below
+    assert_eq!(clamp_pool_to_issuance(999, available_issuance), 999);
+    // Equal
+    assert_eq!(
+        clamp_pool_to_issuance(available_issuance, available_issuance),
+        available_issuance
+    );
+    // Above
+    assert_eq!(
+        clamp_pool_to_issuance(1_001, available_issuance),
+        available_issuance
+    );
+    assert_eq!(
+        clamp_pool_to_issuance(Balance::MAX, available_issuance),
+        available_issuance
+    );
+    assert!(clamp_pool_to_issuance(1_001, 0).is_zero());
+}
+
+#[test]
+fn smart_contract_other_variant_works() {
+    let other_contract = SmartContract::<AccountId32>::Other(7, [0xCD; OTHER_CONTRACT_ADDRESS_LEN]);
+    assert_eq!(other_contract.variant_id(), 2);
+    assert_eq!(other_contract.vm_kind(), VmKind::Other(7));
+    assert_eq!(
+        other_contract.dispatch_target(),
+        DispatchTarget::Other(7, [0xCD; OTHER_CONTRACT_ADDRESS_LEN])
+    );
+
+    // Codec round trip
+    let encoded = other_contract.encode();
+    assert_eq!(encoded[0], 2);
+    assert_eq!(
+        SmartContract::<AccountId32>::decode(&mut &encoded[..]),
+        Ok(other_contract)
+    );
+    assert_eq!(
+        encoded.len(),
+        SmartContract::<AccountId32>::max_encoded_len()
+    );
+
+    // Existing discriminants remain unchanged
+    let evm_contract = SmartContract::<AccountId32>::Evm(H160::repeat_byte(0x01));
+    let wasm_contract = SmartContract::Wasm(AccountId32::new([0x02; 32]));
+    assert_eq!(evm_contract.encode()[0], 0);
+    assert_eq!(wasm_contract.encode()[0], 1);
+    assert_eq!(VmKind::Evm.encode(), vec![0]);
+    assert_eq!(VmKind::Wasm.encode(), vec![1]);
+
+    // Storage key includes the sub-kind tag
+    let key = other_contract.storage_key_bytes();
+    assert_eq!(key.len(), SMART_CONTRACT_KEY_LEN as usize);
+    assert_eq!(&key[..2], &[2, 7]);
+    assert_eq!(&key[2..], &[0xCD; OTHER_CONTRACT_ADDRESS_LEN]);
+    assert_ne!(
+        key,
+        SmartContract::<AccountId32>::Other(8, [0xCD; OTHER_CONTRACT_ADDRESS_LEN])
+            .storage_key_bytes()
+    );
+
+    // Construction from parts
+    assert_eq!(
+        SmartContract::<AccountId32>::from_parts(VmKind::Other(7), &key[2..]),
+        Ok(other_contract)
+    );
+    assert_eq!(
+        SmartContract::<AccountId32>::from_parts(VmKind::Other(7), &key[1..]),
+        Err(SmartContractError::InvalidOtherAddressLength)
+    );
+}
+
+/// Beneficiary resolver used for testing, resolves Wasm contracts to their own account.
+struct TestBeneficiaryResolver;
+impl BeneficiaryResolver<AccountId32> for TestBeneficiaryResolver {
+    fn beneficiary(contract: &SmartContract<AccountId32>) -> Option<AccountId32> {
+        match contract {
+            SmartContract::Wasm(account_id) => Some(account_id.clone()),
+            _ => None,
+        }
+    }
+}
+
+#[test]
+fn beneficiary_resolver_works() {
+    let wasm_account = AccountId32::new([0x05; 32]);
+    let wasm_contract = SmartContract::Wasm(wasm_account.clone());
+    let evm_contract = SmartContract::Evm(H160::repeat_byte(0x05));
+
+    assert_eq!(
+        TestBeneficiaryResolver::beneficiary(&wasm_contract),
+        Some(wasm_account)
+    );
+    assert_eq!(TestBeneficiaryResolver::beneficiary(&evm_contract), None);
+
+    // Default implementation never resolves a beneficiary
+    assert_eq!(
+        <() as BeneficiaryResolver<AccountId32>>::beneficiary(&wasm_contract),
+        None
+    );
+    assert_eq!(
+        <() as BeneficiaryResolver<AccountId32>>::beneficiary(&evm_contract),
+        None
+    );
+}
+
+#[test]
+fn contract_registration_status_transitions() {
+    use ContractRegistrationStatus::*;
+
+    // Legal transitions
+    assert!(ContractRegistrationStatus::can_transition(
+        &Registered,
+        &Unregistering
+    ));
+    assert!(ContractRegistrationStatus::can_transition(
+        &Unregistering,
+        &Unregistered
+    ));
+    assert!(ContractRegistrationStatus::can_transition(
+        &Unregistered,
+        &Registered
+    ));
+
+    // Illegal transitions
+    assert!(!ContractRegistrationStatus::can_transition(
+        &Registered,
+        &Unregistered
+    ));
+    assert!(!ContractRegistrationStatus::can_transition(
+        &Unregistering,
+        &Registered
+    ));
+    assert!(!ContractRegistrationStatus::can_transition(
+        &Unregistered,
+        &Unregistering
+    ));
+    for status in [Registered, Unregistering, Unregistered] {
+        assert!(!ContractRegistrationStatus::can_transition(
+            &status, &status
+        ));
+    }
+}
+
+#[test]
+fn snapshot_from_pools_works() {
+    let cfg = CycleGeometry::from_config::<TestCycleConfig>();
+    let pools = RewardPools {
+        staker: 300,
+        dapp: 200,
+        bonus: 50,
+    };
+
+    let snapshot = snapshot_from_pools(&pools, &cfg);
+
+    // Per-era values are kept as-is
+    assert_eq!(snapshot.base_staker_reward_pool_per_era, pools.staker);
+    assert!(snapshot.adjustable_staker_reward_pool_per_era.is_zero());
+    assert_eq!(snapshot.dapp_reward_pool_per_era, pools.dapp);
+    assert_eq!(snapshot.bonus_reward_pool_per_period, pools.bonus);
+
+    // 2 periods per cycle, each with 5 build&earn eras, so 10 rewarded eras
+    assert_eq!(
+        snapshot.per_cycle,
+        RewardPools {
+            staker: 3_000,
+            dapp: 2_000,
+            bonus: 100,
+        }
+    );
+
+    // Voting eras aren't rewarded, so cycle totals differ from per-era pools times all eras in a cycle
+    let eras_per_cycle = Balance::from(TestCycleConfig::eras_per_cycle());
+    assert_eq!(eras_per_cycle, 12);
+    assert_ne!(snapshot.per_cycle.staker, pools.staker * eras_per_cycle);
+    assert_ne!(snapshot.per_cycle.dapp, pools.dapp * eras_per_cycle);
+    assert_ne!(snapshot.per_cycle.bonus, pools.bonus * eras_per_cycle);
+}
+
+#[test]
+fn smart_contract_is_default_sentinel_works() {
+    // Sentinel
+    assert!(SmartContract::<AccountId32>::default().is_default_sentinel());
+    assert!(SmartContract::<AccountId32>::Evm(H160::repeat_byte(0x01)).is_default_sentinel());
+
+    // Zero address
+    assert!(!SmartContract::<AccountId32>::Evm(H160::zero()).is_default_sentinel());
+
+    // Normal addresses
+    assert!(!SmartContract::<AccountId32>::Evm(H160::repeat_byte(0x02)).is_default_sentinel());
+    assert!(!SmartContract::Wasm(AccountId32::new([0x01; 32])).is_default_sentinel());
+}
+
+struct TestDAppIdProvider(core::cell::Cell<DAppId>);
+impl DAppIdProvider for TestDAppIdProvider {
+    fn next_dapp_id(&self) -> DAppId {
+        self.0.get()
+    }
+
+    fn set_next_dapp_id(&self, next: DAppId) {
+        self.0.set(next)
+    }
+}
+
+#[test]
+fn reserve_range_works() {
+    let provider = TestDAppIdProvider(core::cell::Cell::new(7));
+
+    assert_eq!(reserve_range(&provider, 5), Ok(7..12));
+    assert_eq!(provider.next_dapp_id(), 12);
+
+    // Subsequent reservation continues where the previous one ended
+    assert_eq!(reserve_range(&provider, 1), Ok(12..13));
+    assert_eq!(provider.next_dapp_id(), 13);
+
+    // Empty reservation is a noop
+    assert_eq!(reserve_range(&provider, 0), Ok(13..13));
+    assert_eq!(provider.next_dapp_id(), 13);
+}
+
+#[test]
+fn reserve_range_exhaustion_fails() {
+    let provider = TestDAppIdProvider(core::cell::Cell::new(DAppId::MAX - 3));
+
+    // Not enough space left, provider is left untouched
+    assert_eq!(reserve_range(&provider, 4), Err(()));
+    assert_eq!(provider.next_dapp_id(), DAppId::MAX - 3);
+
+    // Last free Ids can be reserved, sentinel value is never handed out
+    assert_eq!(
+        reserve_range(&provider, 3),
+        Ok(DAppId::MAX - 3..DAppId::MAX)
+    );
+    assert_eq!(provider.next_dapp_id(), DAppId::MAX);
+    assert_eq!(reserve_range(&provider, 1), Err(()));
+}
+
+#[test]
+fn smart_contract_iter_ext_works() {
+    let contracts: Vec<SmartContract<u32>> = vec![
+        SmartContract::Evm(H160::repeat_byte(1)),
+        SmartContract::Wasm(1),
+        SmartContract::Other(7, [1; 32]),
+        SmartContract::Wasm(2),
+        SmartContract::Evm(H160::repeat_byte(2)),
+    ];
+
+    assert_eq!(
+        contracts.clone().into_iter().only_evm().collect::<Vec<_>>(),
+        vec![
+            SmartContract::Evm(H160::repeat_byte(1)),
+            SmartContract::Evm(H160::repeat_byte(2)),
+        ]
+    );
+    assert_eq!(
+        contracts
+            .clone()
+            .into_iter()
+            .only_wasm()
+            .collect::<Vec<_>>(),
+        vec![SmartContract::Wasm(1), SmartContract::Wasm(2)]
+    );
+
+    // Filters are composable & lazy
+    assert_eq!(contracts.into_iter().only_evm().only_wasm().count(), 0);
+    assert_eq!(
+        Vec::<SmartContract<u32>>::new()
+            .into_iter()
+            .only_evm()
+            .count(),
+        0
+    );
+}
+
+#[test]
+fn bonus_allocation_works() {
+    let pool = 1_000;
+    let eligible = vec![(1, 100), (2, 300), (3, 600), (4, 0)];
+
+    let allocations = bonus_allocation(pool, &eligible);
+    assert_eq!(allocations, vec![(1, 100), (2, 300), (3, 600), (4, 0)]);
+    assert_eq!(allocations.iter().map(|(_, a)| a).sum::<Balance>(), pool);
+
+    // Uneven split is rounded down, nothing is over-distributed
+    let eligible = vec![(1, 1), (2, 1), (3, 1)];
+    let allocations = bonus_allocation(pool, &eligible);
+    assert_eq!(allocations, vec![(1, 333), (2, 333), (3, 333)]);
+    assert!(allocations.iter().map(|(_, a)| a).sum::<Balance>() <= pool);
+}
+
+#[test]
+fn bonus_allocation_with_zero_total_stake_is_empty() {
+    assert!(bonus_allocation::<u32>(1_000, &[]).is_empty());
+    assert!(bonus_allocation(1_000, &[(1, 0), (2, 0)]).is_empty());
+}
+
+#[test]
+fn distribute_pool_works() {
+    let pool = 1_000;
+    let sum = |shares: &[(u32, Balance)]| shares.iter().map(|(_, share)| share).sum::<Balance>();
+
+    // Exact split has no dust, regardless of the mode
+    let stakes = vec![(1, 100), (2, 300), (3, 600)];
+    for reassign in [false, true] {
+        let shares = distribute_pool(pool, &stakes, reassign);
+        assert_eq!(shares, vec![(1, 100), (2, 300), (3, 600)]);
+    }
+
+    // Dust is left in the pool
+    let stakes = vec![(1, 1), (2, 2), (3, 1)];
+    let shares = distribute_pool(1_001, &stakes, false);
+    assert_eq!(shares, vec![(1, 250), (2, 500), (3, 250)]);
+    assert_eq!(sum(&shares), 1_000);
+
+    // Dust is reassigned to the largest staker
+    let shares = distribute_pool(1_001, &stakes, true);
+    assert_eq!(shares, vec![(1, 250), (2, 501), (3, 250)]);
+    assert_eq!(sum(&shares), 1_001);
+
+    // On a tie, the first largest staker receives the dust
+    let stakes = vec![(1, 1), (2, 1), (3, 1)];
+    let shares = distribute_pool(pool, &stakes, true);
+    assert_eq!(shares, vec![(1, 334), (2, 333), (3, 333)]);
+
+    // Sum is always exact when reassigning the dust, and never exceeds the pool otherwise
+    let stakes = vec![(1, 7), (2, 13), (3, 1), (4, 0), (5, 29)];
+    for pool in [0, 1, 7, 999, 1_000_003, 123_456_789_012_345] {
+        assert!(sum(&distribute_pool(pool, &stakes, false)) <= pool);
+        assert_eq!(sum(&distribute_pool(pool, &stakes, true)), pool);
+    }
+
+    // Large pool is split exactly, leaving less dust than there are stakers
+    let large_pool = 1_000_000_000_000_000_000_000_007;
+    let stakes = vec![(1, 1), (2, 2)];
+    let shares = distribute_pool(large_pool, &stakes, false);
+    assert_eq!(
+        shares,
+        vec![
+            (1, 333_333_333_333_333_333_333_335),
+            (2, 666_666_666_666_666_666_666_671)
+        ]
+    );
+    assert_eq!(large_pool - sum(&shares), 1);
+
+    // Nothing is distributed if nothing is staked
+    assert!(distribute_pool::<u32>(pool, &[], true).is_empty());
+    assert!(distribute_pool(pool, &[(1, 0), (2, 0)], true).is_empty());
+}
+
+#[test]
+fn contract_payout_policy_resolve_works() {
+    let (owner, treasury, account) = (1, 2, 3);
+
+    assert_eq!(
+        ContractPayoutPolicy::Direct.resolve(&owner, &treasury),
+        Some(owner)
+    );
+    assert_eq!(
+        ContractPayoutPolicy::Treasury.resolve(&owner, &treasury),
+        Some(treasury)
+    );
+    assert_eq!(
+        ContractPayoutPolicy::Account(account).resolve(&owner, &treasury),
+        Some(account)
+    );
+    assert_eq!(ContractPayoutPolicy::Burn.resolve(&owner, &treasury), None);
+}
+
+#[test]
+fn contract_filter_allow_list_works() {
+    let listed = vec![
+        SmartContract::Wasm(1),
+        SmartContract::Evm(H160::repeat_byte(1)),
+    ];
+    let filter = ContractFilter::<u32, 4>::new(
+        BoundedVec::try_from(listed.clone()).unwrap(),
+        FilterMode::AllowList,
+    );
+    assert_eq!(filter.mode(), FilterMode::AllowList);
+
+    // Only the listed contracts are permitted
+    for contract in listed {
+        assert!(filter.permits(&contract));
+    }
+    assert!(!filter.permits(&SmartContract::Wasm(2)));
+    assert!(!filter.permits(&SmartContract::Evm(H160::repeat_byte(2))));
+
+    // Empty allow list permits nothing
+    let filter = ContractFilter::<u32, 4>::new(BoundedVec::default(), FilterMode::AllowList);
+    assert!(!filter.permits(&SmartContract::Wasm(1)));
+}
+
+#[test]
+fn contract_filter_deny_list_works() {
+    let listed = vec![
+        SmartContract::Wasm(1),
+        SmartContract::Evm(H160::repeat_byte(1)),
+    ];
+    let filter = ContractFilter::<u32, 4>::new(
+        BoundedVec::try_from(listed.clone()).unwrap(),
+        FilterMode::DenyList,
+    );
+    assert_eq!(filter.mode(), FilterMode::DenyList);
+
+    // Listed contracts are blocked, all the others are permitted
+    for contract in listed {
+        assert!(!filter.permits(&contract));
+    }
+    assert!(filter.permits(&SmartContract::Wasm(2)));
+    assert!(filter.permits(&SmartContract::Evm(H160::repeat_byte(2))));
+
+    // Empty deny list permits everything
+    let filter = ContractFilter::<u32, 4>::new(BoundedVec::default(), FilterMode::DenyList);
+    assert!(filter.permits(&SmartContract::Wasm(1)));
+}
+
+#[test]
+fn reconcile_works() {
+    let pools = RewardPools {
+        staker: 1_000,
+        dapp: 500,
+        bonus: 100,
+    };
+
+    // Balanced & partial distributions
+    assert_eq!(reconcile(&pools, &pools), Ok(()));
+    assert_eq!(reconcile(&pools, &RewardPools::default()), Ok(()));
+
+    // Staker overshoot
+    let distributed = RewardPools {
+        staker: pools.staker + 1,
+        ..pools
+    };
+    assert_eq!(
+        reconcile(&pools, &distributed),
+        Err(ReconcileError::PoolExceeded(RewardPoolKind::Staker))
+    );
+
+    // Bonus overshoot
+    let distributed = RewardPools {
+        bonus: pools.bonus + 1,
+        ..pools
+    };
+    assert_eq!(
+        reconcile(&pools, &distributed),
+        Err(ReconcileError::PoolExceeded(RewardPoolKind::Bonus))
+    );
+
+    // First offending pool is reported
+    let distributed = RewardPools {
+        dapp: pools.dapp + 1,
+        bonus: pools.bonus + 1,
+        ..pools
+    };
+    assert_eq!(
+        reconcile(&pools, &distributed),
+        Err(ReconcileError::PoolExceeded(RewardPoolKind::DApp))
+    );
+}
+
+#[test]
+fn rpc_smart_contract_round_trip_works() {
+    // EVM
+    let evm_contract = SmartContract::<AccountId32>::Evm(H160::repeat_byte(0xAB));
+    let rpc_contract = RpcSmartContract::from(evm_contract.clone());
+    assert_eq!(rpc_contract.vm, "evm");
+    assert_eq!(rpc_contract.address, format!("0x{}", "ab".repeat(20)));
+    assert_eq!(SmartContract::try_from(rpc_contract), Ok(evm_contract));
+
+    // Wasm
+    let wasm_contract = SmartContract::<AccountId32>::Wasm(AccountId32::new([0x12; 32]));
+    let rpc_contract = RpcSmartContract::from(wasm_contract.clone());
+    assert_eq!(rpc_contract.vm, "wasm");
+    assert_eq!(rpc_contract.address, format!("0x{}", "12".repeat(32)));
+    assert_eq!(SmartContract::try_from(rpc_contract), Ok(wasm_contract));
+
+    // Other
+    let other_contract = SmartContract::<AccountId32>::Other(7, [0x34; 32]);
+    let rpc_contract = RpcSmartContract::from(other_contract.clone());
+    assert_eq!(rpc_contract.vm, "other:7");
+    assert_eq!(SmartContract::try_from(rpc_contract), Ok(other_contract));
+}
+
+#[test]
+fn rpc_smart_contract_invalid_conversion_fails() {
+    let rpc_contract = |vm: &str, address: &str| RpcSmartContract {
+        vm: vm.to_string(),
+        address: address.to_string(),
+    };
+
+    assert_eq!(
+        SmartContract::<AccountId32>::try_from(rpc_contract("ink", "0x12")),
+        Err(RpcSmartContractError::UnknownVm)
+    );
+    assert_eq!(
+        SmartContract::<AccountId32>::try_from(rpc_contract("other:x", "0x12")),
+        Err(RpcSmartContractError::UnknownVm)
+    );
+    assert_eq!(
+        SmartContract::<AccountId32>::try_from(rpc_contract("evm", "0xzz")),
+        Err(RpcSmartContractError::InvalidHex)
+    );
+    assert_eq!(
+        SmartContract::<AccountId32>::try_from(rpc_contract("evm", "0x12")),
+        Err(RpcSmartContractError::InvalidAddress(
+            SmartContractError::InvalidEvmAddressLength
+        ))
+    );
+}
+
+#[test]
+fn adjust_threshold_works() {
+    let current = 1_000;
+    let target = Perbill::from_percent(50);
+    let step = Perbill::from_percent(10);
+
+    // Over target, threshold is raised
+    assert_eq!(
+        adjust_threshold(current, Perbill::from_percent(80), target, step),
+        1_100
+    );
+
+    // Under target, threshold is lowered
+    assert_eq!(
+        adjust_threshold(current, Perbill::from_percent(20), target, step),
+        900
+    );
+
+    // At target, threshold is unchanged
+    assert_eq!(adjust_threshold(current, target, target, step), current);
+
+    // Never overflows, nor goes below zero
+    assert_eq!(
+        adjust_threshold(Balance::MAX, Perbill::one(), target, step),
+        Balance::MAX
+    );
+    assert_eq!(
+        adjust_threshold(current, Perbill::zero(), target, Perbill::one()),
+        0
+    );
+}
+
+#[test]
+fn contract_activity_is_active_works() {
+    let activity = ContractActivity {
+        dapp: 1,
+        era: 7,
+        total_staked: 1_000,
+        staker_count: 3,
+    };
+
+    // Above & exactly at the minimum
+    assert!(activity.is_active(999));
+    assert!(activity.is_active(1_000));
+
+    // Below the minimum
+    assert!(!activity.is_active(1_001));
+
+    // No stakers means no activity
+    let activity = ContractActivity {
+        total_staked: 0,
+        staker_count: 0,
+        ..activity
+    };
+    assert!(!activity.is_active(0));
+}
+
+#[test]
+fn inactive_for_works() {
+    let min_stake = 100;
+    let dapp = 1;
+    let record = |era: EraNumber, total_staked: Balance| ContractActivity {
+        dapp,
+        era,
+        total_staked,
+        staker_count: 1,
+    };
+
+    // Consistently inactive dApp
+    let activity: Vec<_> = (1..=10).map(|era| record(era, min_stake - 1)).collect();
+    assert!(inactive_for(&activity, dapp, 10, 3, min_stake));
+    assert!(inactive_for(&activity, dapp, 10, 10, min_stake));
+    assert!(inactive_for(&activity, dapp, 10, 20, min_stake));
+
+    // Active within the window
+    let mut activity = activity;
+    activity[8] = record(9, min_stake);
+    assert!(inactive_for(&activity, dapp, 10, 1, min_stake));
+    assert!(!inactive_for(&activity, dapp, 10, 2, min_stake));
+    assert!(!inactive_for(&activity, dapp, 10, 10, min_stake));
+
+    // Active, but outside of the window
+    let activity = vec![record(1, 1_000), record(5, 0), record(6, 0)];
+    assert!(inactive_for(&activity, dapp, 6, 2, min_stake));
+    assert!(!inactive_for(&activity, dapp, 6, 6, min_stake));
+
+    // Gaps count as inactivity
+    let activity = vec![record(1, 1_000), record(10, 0)];
+    assert!(inactive_for(&activity, dapp, 10, 9, min_stake));
+    assert!(!inactive_for(&activity, dapp, 10, 10, min_stake));
+
+    // Eras after the latest record count as inactivity too
+    let activity = vec![record(3, 1_000)];
+    assert!(!inactive_for(&activity, dapp, 3, 1, min_stake));
+    assert!(!inactive_for(&activity, dapp, 7, 5, min_stake));
+    assert!(inactive_for(&activity, dapp, 8, 5, min_stake));
+    assert!(inactive_for(&activity, dapp, 100, 5, min_stake));
+
+    // Records after the current era are ignored
+    assert!(inactive_for(&activity, dapp, 2, 2, min_stake));
+
+    // Records of other dApps are ignored
+    let activity = vec![
+        record(9, 0),
+        ContractActivity {
+            dapp: dapp + 1,
+            ..record(10, 1_000)
+        },
+    ];
+    assert!(inactive_for(&activity, dapp, 10, 5, min_stake));
+    assert!(!inactive_for(&activity, dapp + 1, 10, 5, min_stake));
+
+    // No records, or an empty window
+    assert!(inactive_for(&[], dapp, 5, 5, min_stake));
+    assert!(inactive_for(&[record(3, 1_000)], dapp, 3, 0, min_stake));
+}
+
+#[test]
+fn smart_contract_encoded_sizes_are_correct() {
+    // Derived encoding is already `[variant_id][address]`, without any padding to the largest variant.
+    let evm_contract = SmartContract::<AccountId32>::Evm(H160::repeat_byte(1));
+    assert_eq!(evm_contract.encode(), [&[0_u8][..], &[1; 20]].concat());
+    assert_eq!(evm_contract.encode().len(), 21);
+
+    let wasm_contract = SmartContract::Wasm(AccountId32::new([2; 32]));
+    assert_eq!(wasm_contract.encode(), [&[1_u8][..], &[2; 32]].concat());
+    assert_eq!(wasm_contract.encode().len(), 1 + 32);
+
+    let other_contract = SmartContract::<AccountId32>::Other(7, [3; 32]);
+    assert_eq!(other_contract.encode(), [&[2_u8, 7][..], &[3; 32]].concat());
+    assert_eq!(other_contract.encode().len(), 34);
+
+    // Only the bound accounts for the largest variant.
+    assert_eq!(SmartContract::<AccountId32>::max_encoded_len(), 34);
+}
+
+#[test]
+fn smart_contract_decode_rejects_malformed_input() {
+    let encoded = SmartContract::<AccountId32>::Evm(H160::repeat_byte(1)).encode();
+
+    // Truncated input
+    assert!(SmartContract::<AccountId32>::decode(&mut &encoded[..20]).is_err());
+    assert!(SmartContract::<AccountId32>::decode(&mut &[1_u8, 2, 3][..]).is_err());
+    assert!(SmartContract::<AccountId32>::decode(&mut &[][..]).is_err());
+
+    // Trailing bytes
+    let mut extended = encoded.clone();
+    extended.push(0);
+    assert!(SmartContract::<AccountId32>::decode_all(&mut &extended[..]).is_err());
+
+    // Unknown variant
+    let mut invalid = encoded;
+    invalid[0] = 3;
+    assert!(SmartContract::<AccountId32>::decode(&mut &invalid[..]).is_err());
+}
+
+#[test]
+fn top_dapps_by_reward_works() {
+    let rewards = vec![(3, 100), (1, 300), (7, 200), (2, 100), (5, 300)];
+
+    // Ties are broken by the ascending dApp Id
+    assert_eq!(
+        top_dapps_by_reward(&rewards, 3),
+        vec![(1, 300), (5, 300), (7, 200)]
+    );
+    assert_eq!(
+        top_dapps_by_reward(&rewards, 5),
+        vec![(1, 300), (5, 300), (7, 200), (2, 100), (3, 100)]
+    );
+
+    // `n` exceeding the list length returns all, sorted
+    assert_eq!(
+        top_dapps_by_reward(&rewards, 10),
+        top_dapps_by_reward(&rewards, rewards.len())
+    );
+
+    assert!(top_dapps_by_reward(&rewards, 0).is_empty());
+    assert!(top_dapps_by_reward(&[], 3).is_empty());
+}
+
+#[test]
+fn rank_by_stake_works() {
+    // Descending by stake
+    assert_eq!(
+        rank_by_stake(&[(1, 100), (2, 300), (3, 200)]),
+        vec![2, 3, 1]
+    );
+
+    // Ties are broken by the ascending dApp Id
+    assert_eq!(
+        rank_by_stake(&[(7, 100), (3, 300), (5, 100), (1, 300)]),
+        vec![1, 3, 5, 7]
+    );
+
+    // Equal stakes preserve the dApp Id order, regardless of the input order
+    assert_eq!(
+        rank_by_stake(&[(4, 50), (2, 50), (3, 50), (1, 50)]),
+        vec![1, 2, 3, 4]
+    );
+
+    assert!(rank_by_stake(&[]).is_empty());
+}
+
+#[test]
+fn share_of_pool_with_works() {
+    let two_thirds = Perbill::from_rational(2_u32, 3);
+
+    // 7 * 2/3 = 4.67, rounds differently
+    assert_eq!(share_of_pool_with(7, two_thirds, RoundingPolicy::Floor), 4);
+    assert_eq!(
+        share_of_pool_with(7, two_thirds, RoundingPolicy::Nearest),
+        5
+    );
+
+    // 19 * 90% = 17.1, rounds the same
+    let ninety_percent = Perbill::from_percent(90);
+    assert_eq!(
+        share_of_pool_with(19, ninety_percent, RoundingPolicy::Floor),
+        17
+    );
+    assert_eq!(
+        share_of_pool_with(19, ninety_percent, RoundingPolicy::Nearest),
+        17
+    );
+
+    // Exact shares aren't rounded
+    for policy in [RoundingPolicy::Floor, RoundingPolicy::Nearest] {
+        assert_eq!(
+            share_of_pool_with(1_000, Perbill::from_percent(25), policy),
+            250
+        );
+        assert_eq!(share_of_pool_with(1_000, Perbill::zero(), policy), 0);
+        assert_eq!(share_of_pool_with(1_000, Perbill::one(), policy), 1_000);
+    }
+
+    // Floor-only wrapper
+    assert_eq!(share_of_pool(7, two_thirds), 4);
+    assert_eq!(
+        share_of_pool(1_000_003, two_thirds),
+        share_of_pool_with(1_000_003, two_thirds, RoundingPolicy::Floor)
+    );
+}
+
+#[test]
+fn carry_over_bonus_works() {
+    // Carry & burn
+    assert_eq!(carry_over_bonus(1_000, CarryOverPolicy::Carry), (1_000, 0));
+    assert_eq!(carry_over_bonus(1_000, CarryOverPolicy::Burn), (0, 1_000));
+
+    // Split, with the carried amount rounded down
+    assert_eq!(
+        carry_over_bonus(1_000, CarryOverPolicy::Split(Perbill::from_percent(30))),
+        (300, 700)
+    );
+    assert_eq!(
+        carry_over_bonus(999, CarryOverPolicy::Split(Perbill::from_percent(50))),
+        (499, 500)
+    );
+
+    // Carried & burned always add up to the unclaimed amount
+    for unclaimed in [0, 1, 7, 999, 1_000_003, Balance::MAX] {
+        for percent in [0, 1, 33, 50, 99, 100] {
+            let (carried, burned) = carry_over_bonus(
+                unclaimed,
+                CarryOverPolicy::Split(Perbill::from_percent(percent)),
+            );
+            assert_eq!(carried + burned, unclaimed);
+        }
+
+        for policy in [CarryOverPolicy::Carry, CarryOverPolicy::Burn] {
+            let (carried, burned) = carry_over_bonus(unclaimed, policy);
+            assert_eq!(carried + burned, unclaimed);
+        }
+    }
+}
+
+#[test]
+fn apply_reward_fee_works() {
+    // Fee is rounded down
+    assert_eq!(
+        apply_reward_fee(1_000, Perbill::from_percent(10)),
+        (900, 100)
+    );
+    assert_eq!(apply_reward_fee(999, Perbill::from_percent(10)), (900, 99));
+
+    // No fee & full fee
+    assert_eq!(
+        apply_reward_fee(1_000, Perbill::from_percent(0)),
+        (1_000, 0)
+    );
+    assert_eq!(
+        apply_reward_fee(1_000, Perbill::from_percent(100)),
+        (0, 1_000)
+    );
+
+    // Net & fee always add up to the gross amount
+    for gross in [0, 1, 7, 999, 1_000_003, Balance::MAX] {
+        for fee in [0, 1, 33, 50, 99, 100] {
+            let (net, fee_amount) = apply_reward_fee(gross, Perbill::from_percent(fee));
+            assert_eq!(net + fee_amount, gross);
+        }
+    }
+}
+
+#[test]
+fn try_from_contract_origin_works() {
+    let who = AccountId32::new([0xAB; 32]);
+
+    // EVM origin cannot be reversed by default
+    assert_eq!(SmartContract::try_from_contract_origin(&who, true), None);
+
+    // Wasm origin is mapped directly
+    assert_eq!(
+        SmartContract::try_from_contract_origin(&who, false),
+        Some(SmartContract::Wasm(who.clone()))
+    );
+}
+
+#[test]
+fn promotion_queue_ordering_works() {
+    let mut queue = PromotionQueue::<ConstU32<5>>::new();
+    assert!(queue.is_empty());
+
+    // Ordered by stake descending, then by insertion order
+    assert_eq!(queue.push(1, 100), Ok(()));
+    assert_eq!(queue.push(2, 300), Ok(()));
+    assert_eq!(queue.push(3, 100), Ok(()));
+    assert_eq!(queue.push(4, 200), Ok(()));
+    assert_eq!(queue.push(5, 300), Ok(()));
+    assert_eq!(queue.len(), 5);
+
+    let order: Vec<_> = (1..=5).map(|dapp| queue.position(dapp)).collect();
+    assert_eq!(order, vec![Some(3), Some(0), Some(4), Some(2), Some(1)]);
+
+    // Full queue & duplicate entries are rejected
+    assert_eq!(queue.push(6, 1_000), Err(()));
+    queue.pop_highest();
+    assert_eq!(queue.push(1, 1_000), Err(()));
+}
+
+#[test]
+fn promotion_queue_pop_highest_works() {
+    let mut queue = PromotionQueue::<ConstU32<5>>::new();
+    assert_eq!(queue.pop_highest(), None);
+
+    assert_eq!(queue.push(1, 100), Ok(()));
+    assert_eq!(queue.push(2, 300), Ok(()));
+    assert_eq!(queue.push(3, 300), Ok(()));
+
+    assert_eq!(queue.pop_highest(), Some((2, 300)));
+    assert_eq!(queue.pop_highest(), Some((3, 300)));
+    assert_eq!(queue.pop_highest(), Some((1, 100)));
+    assert_eq!(queue.pop_highest(), None);
+    assert!(queue.is_empty());
+}
+
+#[test]
+fn promotion_queue_position_works() {
+    let mut queue = PromotionQueue::<ConstU32<5>>::new();
+    assert_eq!(queue.position(1), None);
+
+    assert_eq!(queue.push(1, 100), Ok(()));
+    assert_eq!(queue.push(2, 50), Ok(()));
+    assert_eq!(queue.position(1), Some(0));
+    assert_eq!(queue.position(2), Some(1));
+    assert_eq!(queue.position(3), None);
+
+    // Higher stake jumps ahead, moving the others back
+    assert_eq!(queue.push(3, 150), Ok(()));
+    assert_eq!(queue.position(3), Some(0));
+    assert_eq!(queue.position(1), Some(1));
+    assert_eq!(queue.position(2), Some(2));
+
+    // Popped entry is no longer queued
+    queue.pop_highest();
+    assert_eq!(queue.position(3), None);
+    assert_eq!(queue.position(1), Some(0));
+}
+
+#[test]
+fn evm_checksummed_works() {
+    // Test vectors from EIP-55
+    let test_vectors = [
+        "0x52908400098527886E0F7030069857D2E4169EE7",
+        "0x8617E340B3D01FA5F11F306F4090FD50E238070D",
+        "0xde709f2102306220921060314715629080e2fb77",
+        "0x27b1fdb04752bbc536007a920d24acb045561c26",
+        "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+        "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+        "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+        "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+    ];
+
+    for expected in test_vectors {
+        let address = H160::from_slice(
+            &sp_core::bytes::from_hex(&expected.to_lowercase()).expect("Valid hex."),
+        );
+        assert_eq!(
+            SmartContract::<AccountId32>::Evm(address).evm_checksummed(),
+            Some(expected.to_string())
+        );
+    }
+
+    // Non-EVM contracts
+    assert!(SmartContract::Wasm(AccountId32::new([1; 32]))
+        .evm_checksummed()
+        .is_none());
+    assert!(SmartContract::<AccountId32>::Other(7, [1; 32])
+        .evm_checksummed()
+        .is_none());
+}
+
+#[test]
+fn per_pool_works() {
+    let mut per_pool = PerPool {
+        staker: 10_u32,
+        dapp: 20,
+        bonus: 30,
+    };
+
+    // Get
+    assert_eq!(*per_pool.get(RewardPoolKind::Staker), 10);
+    assert_eq!(*per_pool.get(RewardPoolKind::DApp), 20);
+    assert_eq!(*per_pool.get(RewardPoolKind::Bonus), 30);
+
+    // Mutable get only affects the specified kind
+    for (idx, kind) in RewardPoolKind::all().into_iter().enumerate() {
+        *per_pool.get_mut(kind) += 1;
+
+        for (other_idx, other_kind) in RewardPoolKind::all().into_iter().enumerate() {
+            let expected = (other_idx as u32 + 1) * 10 + u32::from(other_idx <= idx);
+            assert_eq!(*per_pool.get(other_kind), expected);
+        }
+    }
+
+    // Map keeps the values assigned to the same kinds
+    let mapped = per_pool.map(|value| Balance::from(value) * 2);
+    for kind in RewardPoolKind::all() {
+        assert_eq!(*mapped.get(kind), Balance::from(*per_pool.get(kind)) * 2);
+    }
+    assert_eq!(
+        mapped,
+        PerPool {
+            staker: 22,
+            dapp: 42,
+            bonus: 62,
+        }
+    );
+}
+
+#[test]
+fn cross_chain_id_works() {
+    let evm_address = H160::repeat_byte(0xAB);
+    let evm_id = SmartContract::<AccountId32>::Evm(evm_address).cross_chain_id();
+    assert_eq!(evm_id.vm_kind, VmKind::Evm);
+    assert_eq!(evm_id.key.len(), 20);
+    assert_eq!(evm_id.key.as_slice(), evm_address.as_bytes());
+
+    let wasm_account = AccountId32::new([7; 32]);
+    let wasm_id = SmartContract::Wasm(wasm_account.clone()).cross_chain_id();
+    assert_eq!(wasm_id.vm_kind, VmKind::Wasm);
+    assert_eq!(wasm_id.key.len(), 32);
+    assert_eq!(
+        wasm_id.key.as_slice(),
+        AsRef::<[u8; 32]>::as_ref(&wasm_account)
+    );
+
+    let other_id = SmartContract::<AccountId32>::Other(3, [9; 32]).cross_chain_id();
+    assert_eq!(other_id.vm_kind, VmKind::Other(3));
+    assert_eq!(other_id.key.as_slice(), &[9; 32]);
+
+    // Descriptors of different variants with the same bytes are distinct
+    assert_ne!(
+        SmartContract::Wasm(AccountId32::new([9; 32])).cross_chain_id(),
+        other_id
+    );
+}
+
+thread_local! {
+    pub(crate) static REGISTERED_DAPPS: RefCell<BTreeMap<DAppId, SmartContract<u32>>> = RefCell::new(BTreeMap::new());
+}
+
+/// Lookup used for testing, backed by a map of registered dApps.
+struct TestDAppLookup;
+impl DAppLookup<u32> for TestDAppLookup {
+    fn contract_of(id: DAppId) -> Option<SmartContract<u32>> {
+        REGISTERED_DAPPS.with(|v| v.borrow().get(&id).cloned())
+    }
+
+    fn id_of(contract: &SmartContract<u32>) -> Option<DAppId> {
+        REGISTERED_DAPPS.with(|v| {
+            v.borrow()
+                .iter()
+                .find(|(_, registered)| *registered == contract)
+                .map(|(id, _)| *id)
+        })
+    }
+}
+
+#[test]
+fn dapp_lookup_works() {
+    // Default lookup knows nothing
+    assert!(<() as DAppLookup<u32>>::contract_of(0).is_none());
+    assert!(<() as DAppLookup<u32>>::id_of(&SmartContract::Wasm(1)).is_none());
+
+    let contracts = [
+        (1, SmartContract::Wasm(10)),
+        (2, SmartContract::Evm(H160::repeat_byte(0x20))),
+        (5, SmartContract::Other(3, [0x50; 32])),
+    ];
+    REGISTERED_DAPPS.with(|v| v.borrow_mut().extend(contracts.iter().cloned()));
+
+    // Both directions are consistent
+    for (id, contract) in contracts.iter() {
+        assert_eq!(TestDAppLookup::contract_of(*id).as_ref(), Some(contract));
+        assert_eq!(TestDAppLookup::id_of(contract), Some(*id));
+
+        let looked_up = TestDAppLookup::contract_of(*id).expect("Registered.");
+        assert_eq!(TestDAppLookup::id_of(&looked_up), Some(*id));
+    }
+
+    // Unknown entries
+    assert!(TestDAppLookup::contract_of(3).is_none());
+    assert!(TestDAppLookup::id_of(&SmartContract::Wasm(11)).is_none());
+}
+
+#[test]
+fn pool_history_works() {
+    const K: usize = 4;
+    let pools = |era: u128| RewardPools {
+        staker: era,
+        dapp: era * 10,
+        bonus: era * 100,
+    };
+
+    let mut history = PoolHistory::<K>::new();
+    assert!(history.is_empty());
+    assert!(history.recent(K).next().is_none());
+
+    // Partially filled history
+    history.push(pools(1));
+    history.push(pools(2));
+    assert_eq!(history.len(), 2);
+    assert_eq!(
+        history.recent(K).cloned().collect::<Vec<_>>(),
+        vec![pools(2), pools(1)]
+    );
+
+    // Pushing `K + 2` entries only retains the last `K`, in order
+    for era in 3..=K as u128 + 2 {
+        history.push(pools(era));
+    }
+    assert_eq!(history.len(), K);
+    assert_eq!(
+        history.recent(K + 2).cloned().collect::<Vec<_>>(),
+        vec![pools(6), pools(5), pools(4), pools(3)]
+    );
+
+    // Fewer entries than stored can be requested
+    assert_eq!(
+        history.recent(2).cloned().collect::<Vec<_>>(),
+        vec![pools(6), pools(5)]
+    );
+    assert!(history.recent(0).next().is_none());
+
+    // Wraparound is consistent over many pushes
+    for era in 7..=100 {
+        history.push(pools(era));
+    }
+    assert_eq!(history.len(), K);
+    assert_eq!(
+        history.recent(K).cloned().collect::<Vec<_>>(),
+        vec![pools(100), pools(99), pools(98), pools(97)]
+    );
+}
+
+#[test]
+fn pool_history_zero_capacity_is_noop() {
+    let mut history = PoolHistory::<0>::new();
+    history.push(RewardPools::default());
+
+    assert!(history.is_empty());
+    assert!(history.recent(1).next().is_none());
+}
+
+#[test]
+fn vm_policy_permits_works() {
+    let evm_contract = SmartContract::<u32>::Evm(H160::repeat_byte(1));
+    let wasm_contract = SmartContract::<u32>::Wasm(1);
+    let other_contract = SmartContract::<u32>::Other(3, [1; 32]);
+
+    // Both
+    assert!(vm_policy_permits(VmPolicy::Both, &evm_contract));
+    assert!(vm_policy_permits(VmPolicy::Both, &wasm_contract));
+    assert!(vm_policy_permits(VmPolicy::Both, &other_contract));
+
+    // EVM only
+    assert!(vm_policy_permits(VmPolicy::EvmOnly, &evm_contract));
+    assert!(!vm_policy_permits(VmPolicy::EvmOnly, &wasm_contract));
+    assert!(!vm_policy_permits(VmPolicy::EvmOnly, &other_contract));
+
+    // Wasm only
+    assert!(!vm_policy_permits(VmPolicy::WasmOnly, &evm_contract));
+    assert!(vm_policy_permits(VmPolicy::WasmOnly, &wasm_contract));
+    assert!(!vm_policy_permits(VmPolicy::WasmOnly, &other_contract));
+}
+
+#[test]
+fn reward_distributed_codec_round_trip_works() {
+    for kind in RewardPoolKind::all() {
+        let event = RewardDistributed {
+            era: 17,
+            beneficiary: AccountId32::new([5; 32]),
+            amount: 1_000_000_000_000_000_000,
+            kind,
+        };
+
+        let encoded = event.encode();
+        assert!(encoded.len() <= RewardDistributed::<AccountId32>::max_encoded_len());
+        assert_eq!(
+            RewardDistributed::<AccountId32>::decode_all(&mut &encoded[..]).ok(),
+            Some(event)
+        );
+    }
+}
+
+#[test]
+fn contract_lru_eviction_order_works() {
+    let mut cache = ContractLru::<u32, u32, 2>::new();
+    assert!(cache.is_empty());
+    assert!(cache.get(&SmartContract::Wasm(1)).is_none());
+
+    cache.put(SmartContract::Wasm(1), 10);
+    cache.put(SmartContract::Wasm(2), 20);
+    assert_eq!(cache.len(), 2);
+
+    // Least recently used entry is evicted
+    cache.put(SmartContract::Wasm(3), 30);
+    assert_eq!(cache.len(), 2);
+    assert!(cache.get(&SmartContract::Wasm(1)).is_none());
+    assert_eq!(cache.get(&SmartContract::Wasm(2)), Some(&20));
+    assert_eq!(cache.get(&SmartContract::Wasm(3)), Some(&30));
+
+    // Updating an existing entry doesn't evict anything, but refreshes its recency
+    cache.put(SmartContract::Wasm(2), 21);
+    assert_eq!(cache.len(), 2);
+    cache.put(SmartContract::Evm(H160::repeat_byte(4)), 40);
+    assert!(cache.get(&SmartContract::Wasm(3)).is_none());
+    assert_eq!(cache.get(&SmartContract::Wasm(2)), Some(&21));
+    assert_eq!(
+        cache.get(&SmartContract::Evm(H160::repeat_byte(4))),
+        Some(&40)
+    );
+}
+
+#[test]
+fn contract_lru_hit_promotes_recency() {
+    let mut cache = ContractLru::<u32, u32, 3>::new();
+    for id in 1..=3 {
+        cache.put(SmartContract::Wasm(id), id * 10);
+    }
+
+    // Hit promotes the oldest entry, so the next oldest one is evicted instead
+    assert_eq!(cache.get(&SmartContract::Wasm(1)), Some(&10));
+    cache.put(SmartContract::Wasm(4), 40);
+    assert!(cache.get(&SmartContract::Wasm(2)).is_none());
+
+    // Eviction continues in the recency order
+    cache.put(SmartContract::Wasm(5), 50);
+    assert!(cache.get(&SmartContract::Wasm(3)).is_none());
+    for id in [1, 4, 5] {
+        assert_eq!(cache.get(&SmartContract::Wasm(id)), Some(&(id * 10)));
+    }
+
+    // Zero capacity cache never holds anything
+    let mut cache = ContractLru::<u32, u32, 0>::new();
+    cache.put(SmartContract::Wasm(1), 10);
+    assert!(cache.is_empty());
+    assert!(cache.get(&SmartContract::Wasm(1)).is_none());
+}
+
+#[test]
+fn pack_and_unpack_contracts_round_trip_works() {
+    let contracts = vec![
+        SmartContract::<AccountId32>::Evm(H160::repeat_byte(1)),
+        SmartContract::Wasm(AccountId32::new([2; 32])),
+        SmartContract::Other(3, [3; 32]),
+        SmartContract::Evm(H160::repeat_byte(4)),
+    ];
+
+    let packed = pack_contracts(&contracts);
+    assert_eq!(
+        packed.len(),
+        contracts
+            .iter()
+            .map(|contract| 1 + contract.encoded_size())
+            .sum::<usize>()
+    );
+    assert_eq!(
+        unpack_contracts::<AccountId32>(&packed).ok(),
+        Some(contracts)
+    );
+
+    // Empty list
+    assert!(pack_contracts::<AccountId32>(&[]).is_empty());
+    assert_eq!(unpack_contracts::<AccountId32>(&[]).ok(), Some(vec![]));
+}
+
+#[test]
+fn unpack_contracts_rejects_truncated_buffers() {
+    let contracts = vec![
+        SmartContract::<AccountId32>::Evm(H160::repeat_byte(1)),
+        SmartContract::Wasm(AccountId32::new([2; 32])),
+    ];
+    let packed = pack_contracts(&contracts);
+
+    // Every truncation is rejected, except the one on the entry boundary
+    let entry_boundary = 1 + contracts[0].encoded_size();
+    for len in 1..packed.len() {
+        let result = unpack_contracts::<AccountId32>(&packed[..len]);
+        if len == entry_boundary {
+            assert_eq!(result.ok(), Some(vec![contracts[0].clone()]));
+        } else {
+            assert!(result.is_err(), "truncated to {} bytes", len);
+        }
+    }
+
+    // Entry with trailing bytes
+    let mut invalid = pack_contracts(&contracts[..1]);
+    invalid[0] = parity_scale_codec::Compact(contracts[0].encoded_size() as u32 + 1).encode()[0];
+    invalid.push(0);
+    assert!(unpack_contracts::<AccountId32>(&invalid).is_err());
+}
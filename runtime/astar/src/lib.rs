@@ -444,6 +444,7 @@ impl pallet_inflation::Config for Runtime {
     type Currency = Balances;
     type PayoutPerBlock = InflationPayoutPerBlock;
     type CycleConfiguration = InflationCycleConfig;
+    type Observers = ();
     type RuntimeEvent = RuntimeEvent;
     type WeightInfo = weights::pallet_inflation::SubstrateWeight<Runtime>;
 }